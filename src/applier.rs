@@ -0,0 +1,528 @@
+//! Applying a license to a project in one step, as the binaries do.
+
+use std::borrow::Borrow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+use serde_json::Value;
+use toml_edit::{Document, Item};
+
+use crate::{Config, Expr, Journal, Plan, Renderer, CONFIG_FILE};
+
+/// Applies a license to a project: resolves the authors, parses the license expression, renders
+/// the license files, and plans writing them and updating the project's manifests.
+///
+/// The configuration file of the project, [`CONFIG_FILE`], is loaded unless another configuration
+/// is given with [`Applier::config`], so subdirectories and documentation that are licensed
+/// differently get their own license files, and the license text is rendered as configured, as
+/// with the binaries. The license text can be customized further with [`Applier::renderer`].
+///
+/// Unless authors are given with [`Applier::author`], they're read from the project's
+/// `Cargo.toml` or `package.json`.
+///
+/// # Example
+///
+/// ```no_run
+/// use apply_license::Applier;
+///
+/// Applier::new("MIT OR Apache-2.0")
+///     .dir("path/to/project")
+///     .author("John Doe <jd@example.com>")
+///     .update_manifests(true)
+///     .apply()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Applier {
+    license_expr: String,
+    dir: PathBuf,
+    output_dir: Option<PathBuf>,
+    authors: Vec<String>,
+    renderer: Option<Renderer>,
+    config: Option<Config>,
+    update_manifests: bool,
+}
+
+impl Applier {
+    /// Creates an applier for the SPDX license expression `license_expr`, such as `MIT`, in the
+    /// current directory.
+    pub fn new(license_expr: impl Into<String>) -> Applier {
+        Applier {
+            license_expr: license_expr.into(),
+            dir: PathBuf::new(),
+            output_dir: None,
+            authors: vec![],
+            renderer: None,
+            config: None,
+            update_manifests: false,
+        }
+    }
+
+    /// Sets the directory of the project.
+    pub fn dir(&mut self, dir: impl Into<PathBuf>) -> &mut Applier {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Sets the directory to write the license files to, such as the root of a monorepo, instead of
+    /// the directory of the project.
+    pub fn output_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Applier {
+        self.output_dir = Some(dir.into());
+        self
+    }
+
+    /// Adds an author to name in copyright lines. The author may be a git-style author with an
+    /// email address, or several authors separated by commas, as with
+    /// [`parse_authors`](crate::parse_authors). Duplicate authors are only named once.
+    pub fn author(&mut self, author: impl Into<String>) -> &mut Applier {
+        self.authors.push(author.into());
+        self
+    }
+
+    /// Sets the renderer of the license files, such as one with a custom copyright line, instead of
+    /// one configured by [`Renderer::configure`] with the project's configuration.
+    pub fn renderer(&mut self, renderer: Renderer) -> &mut Applier {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Sets the configuration of the project, instead of loading it from [`CONFIG_FILE`].
+    pub fn config(&mut self, config: Config) -> &mut Applier {
+        self.config = Some(config);
+        self
+    }
+
+    /// Sets whether to set the license field of the project's `Cargo.toml`, `package.json`, or
+    /// `pyproject.toml`, if it has any.
+    pub fn update_manifests(&mut self, update: bool) -> &mut Applier {
+        self.update_manifests = update;
+        self
+    }
+
+    /// Returns a plan of the changes that applying the license would make, without making them.
+    ///
+    /// Fails if no authors are given or found in the project's manifests, unless the renderer
+    /// doesn't name the authors in copyright lines.
+    pub fn plan(&self) -> Result<Plan> {
+        Expr::parse(&self.license_expr)?;
+
+        let config = match &self.config {
+            Some(config) => config.clone(),
+            None => Config::load(&self.dir)?,
+        };
+
+        let renderer = match &self.renderer {
+            Some(renderer) => renderer.clone(),
+            None => {
+                let mut renderer = Renderer::new();
+                if let Some(project) = project_name(&self.dir) {
+                    renderer.var("project", project);
+                }
+                renderer.configure(&config);
+                renderer
+            }
+        };
+
+        let authors = if self.authors.is_empty() {
+            manifest_authors(&self.dir)?
+        } else {
+            self.authors.clone()
+        };
+        let authors = authors.iter().map(String::as_str).collect::<Vec<_>>();
+        let names = if !authors.is_empty() {
+            crate::parse_author_names(&authors)?
+        } else if renderer.names_authors() {
+            bail!(
+                "no authors found in the manifests in {} (add one with Applier::author)",
+                self.dir.display()
+            );
+        } else {
+            vec![]
+        };
+
+        let mut plan = Plan::new();
+        plan.license(&self.license_expr);
+
+        let output_dir = self.output_dir.as_ref().unwrap_or(&self.dir);
+        plan_license_files(&mut plan, output_dir, &renderer, &self.license_expr, &names)?;
+        plan_path_licenses(&mut plan, &self.dir, &config, &renderer, &names)?;
+        plan_docs_license(&mut plan, &self.dir, &config, None, &renderer, &names)?;
+
+        let third_party_dir = self.dir.join(crate::THIRD_PARTY_DIR);
+        for dir in &config.third_party {
+            plan.append(crate::plan_third_party_licenses(
+                &self.dir.join(dir),
+                &third_party_dir,
+            )?);
+        }
+
+        if self.update_manifests {
+            plan_cargo_manifest(&mut plan, &self.dir, &self.license_expr)?;
+            plan.append(plan_package_manifests(&self.dir, &self.license_expr)?);
+        }
+
+        Ok(plan)
+    }
+
+    /// Applies the license, returning a journal of the changes that were made.
+    pub fn apply(&self) -> Result<Journal> {
+        self.plan()?.execute(|_| ())
+    }
+}
+
+/// Adds actions to the plan that write the license files for `license_expr`, including the text of
+/// any exceptions, to `dir`, and remove any license files left over from a different layout.
+///
+/// The plan includes a warning for each file in `dir` that looks like a license file, but isn't
+/// named like one.
+pub fn plan_license_files<S: Borrow<str>>(
+    plan: &mut Plan,
+    dir: &Path,
+    renderer: &Renderer,
+    license_expr: &str,
+    authors: &[S],
+) -> Result<()> {
+    let licenses = crate::parse_spdx(license_expr)?;
+
+    let mut renderer = renderer.clone();
+    for (license, exception) in crate::parse_exceptions(license_expr)? {
+        renderer.exception(license, exception);
+    }
+
+    let files = renderer
+        .render(&licenses, authors)?
+        .into_iter()
+        .map(|(name, contents)| (dir.join(name), contents))
+        .collect();
+
+    for path in crate::stale_license_files(&files) {
+        plan.remove_file(path);
+    }
+
+    for path in crate::nonstandard_license_files(dir)? {
+        plan.warn(format!(
+            "{} looks like a license file (run `apply-license migrate` to rename it)",
+            path.display()
+        ));
+    }
+
+    for (path, contents) in files {
+        plan.write_file(path, contents);
+    }
+
+    Ok(())
+}
+
+/// Adds actions to the plan that write license files for each subdirectory of the project in
+/// `root` that the configuration licenses differently from the rest of the project.
+pub fn plan_path_licenses<S: Borrow<str>>(
+    plan: &mut Plan,
+    root: &Path,
+    config: &Config,
+    renderer: &Renderer,
+    authors: &[S],
+) -> Result<()> {
+    for (dir, license_expr) in &config.paths {
+        let dir = &root.join(dir);
+        if !dir.is_dir() {
+            plan.warn(format!(
+                "{} is configured in {}, but it is not a directory",
+                dir.display(),
+                CONFIG_FILE
+            ));
+            continue;
+        }
+
+        debug!(
+            "applying license expression {} to {}",
+            license_expr,
+            dir.display()
+        );
+        plan_license_files(plan, dir, renderer, license_expr, authors)
+            .with_context(|| format!("invalid license for {} in {}", dir.display(), CONFIG_FILE))?;
+    }
+
+    Ok(())
+}
+
+/// Adds actions to the plan that write the license files for the documentation of the project in
+/// `root`, if it's licensed under `docs_license` or the license in the configuration.
+pub fn plan_docs_license<S: Borrow<str>>(
+    plan: &mut Plan,
+    root: &Path,
+    config: &Config,
+    docs_license: Option<&str>,
+    renderer: &Renderer,
+    authors: &[S],
+) -> Result<()> {
+    let license_expr = match docs_license.or(config.docs_license.as_deref()) {
+        Some(license_expr) => license_expr,
+        None => return Ok(()),
+    };
+    Expr::parse(license_expr).context("invalid documentation license")?;
+
+    let dir = &root.join(config.docs_dir());
+    if !dir.is_dir() {
+        plan.warn(format!(
+            "the documentation license was not applied, because {} is not a directory",
+            dir.display()
+        ));
+        return Ok(());
+    }
+
+    debug!(
+        "applying license expression {} to {}",
+        license_expr,
+        dir.display()
+    );
+    plan_license_files(plan, dir, renderer, license_expr, authors)
+        .context("invalid documentation license")
+}
+
+/// Returns the name of the project in `dir`, which is the name of the directory, for the `project`
+/// template variable.
+pub fn project_name(dir: &Path) -> Option<String> {
+    crate::existing_dir(dir)
+        .canonicalize()
+        .ok()?
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Returns the authors listed in the `Cargo.toml` or `package.json` in `dir`, if there are any.
+///
+/// The `author` of a `package.json` may be a string, such as `Jane Doe <jd@example.com>
+/// (https://example.com)`, or an object with a `name` and an `email`.
+fn manifest_authors(dir: &Path) -> Result<Vec<String>> {
+    let cargo_toml = dir.join("Cargo.toml");
+    if cargo_toml.exists() {
+        let manifest: Document = fs::read_to_string(&cargo_toml)?
+            .parse()
+            .with_context(|| format!("could not parse {}", cargo_toml.display()))?;
+
+        let authors = manifest
+            .get("package")
+            .and_then(|package| package.get("authors"))
+            .and_then(Item::as_array)
+            .map(|authors| authors.iter().filter_map(|author| author.as_str()))
+            .into_iter()
+            .flatten()
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        if !authors.is_empty() {
+            debug!("using the authors in {}", cargo_toml.display());
+            return Ok(authors);
+        }
+    }
+
+    let package_json = dir.join("package.json");
+    if package_json.exists() {
+        let manifest: Value = serde_json::from_str(&fs::read_to_string(&package_json)?)
+            .with_context(|| format!("could not parse {}", package_json.display()))?;
+
+        let author = match &manifest["author"] {
+            // The author's URL isn't part of a git-style author.
+            Value::String(author) => match author.trim_end().rsplit_once(" (") {
+                Some((author, url)) if url.ends_with(')') => author.to_owned(),
+                _ => author.clone(),
+            },
+            Value::Object(author) => match (author.get("name"), author.get("email")) {
+                (Some(Value::String(name)), Some(Value::String(email))) => {
+                    format!("{} <{}>", name, email)
+                }
+                (Some(Value::String(name)), _) => name.clone(),
+                _ => return Ok(vec![]),
+            },
+            _ => return Ok(vec![]),
+        };
+        debug!("using the author in {}", package_json.display());
+        return Ok(vec![author]);
+    }
+
+    Ok(vec![])
+}
+
+/// Adds an action to the plan that sets `package.license` in the `Cargo.toml` in `dir`, if there
+/// is one, returning the name of the package.
+pub fn plan_cargo_manifest(
+    plan: &mut Plan,
+    dir: &Path,
+    license_expr: &str,
+) -> Result<Option<String>> {
+    let manifest_path = dir.join("Cargo.toml");
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+
+    let manifest: Document = fs::read_to_string(&manifest_path)?
+        .parse()
+        .with_context(|| format!("could not parse {}", manifest_path.display()))?;
+
+    let package = match manifest.get("package") {
+        Some(package) => package,
+        None => return Ok(None),
+    };
+
+    if package.get("license").and_then(Item::as_str) != Some(license_expr) {
+        plan.update_manifest(manifest_path, "package.license", license_expr);
+    }
+
+    Ok(package
+        .get("name")
+        .and_then(Item::as_str)
+        .map(str::to_owned))
+}
+
+/// Returns a plan that sets the license in the `package.json` and `pyproject.toml` in `dir`, if
+/// there are any.
+///
+/// In `pyproject.toml`, `project.license` is set, or `tool.poetry.license` for a Poetry project
+/// without a `[project]` table.
+pub fn plan_package_manifests(dir: &Path, license_expr: &str) -> Result<Plan> {
+    let mut plan = Plan::new();
+
+    let package_json = dir.join("package.json");
+    if package_json.exists() {
+        let manifest: Value = serde_json::from_str(&fs::read_to_string(&package_json)?)
+            .with_context(|| format!("could not parse {}", package_json.display()))?;
+
+        if manifest.get("license").and_then(Value::as_str) != Some(license_expr) {
+            plan.update_manifest(package_json, "license", license_expr);
+        }
+    }
+
+    let pyproject = dir.join("pyproject.toml");
+    if pyproject.exists() {
+        let manifest: Document = fs::read_to_string(&pyproject)?
+            .parse()
+            .with_context(|| format!("could not parse {}", pyproject.display()))?;
+
+        // Poetry keeps the license in its own table, unless the project uses the standard one.
+        let field = if manifest.get("project").is_some() {
+            "project.license"
+        } else if manifest
+            .get("tool")
+            .and_then(|tool| tool.get("poetry"))
+            .is_some()
+        {
+            "tool.poetry.license"
+        } else {
+            return Ok(plan);
+        };
+
+        // An older `license = { text = "..." }` table is replaced with the expression itself.
+        let current = field
+            .split('.')
+            .try_fold(manifest.as_item(), |item, key| item.get(key))
+            .and_then(Item::as_str);
+        if current != Some(license_expr) {
+            plan.update_manifest(pyproject, field, license_expr);
+        }
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::{Action, Applier, Config};
+
+    #[test]
+    fn apply() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+        fs::write(dir.join("LICENSE"), "old")?;
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"foo\"\n")?;
+        fs::write(dir.join("package.json"), "{\n  \"name\": \"foo\"\n}\n")?;
+
+        let mut applier = Applier::new("MIT OR Apache-2.0");
+        applier.dir(dir).author("John Doe <jd@example.com>");
+
+        let plan = applier.plan()?;
+        assert!(plan
+            .actions
+            .iter()
+            .all(|action| !matches!(action, Action::UpdateManifest { .. })));
+
+        applier.update_manifests(true).apply()?;
+
+        assert!(!dir.join("LICENSE").exists());
+        assert!(fs::read_to_string(dir.join("LICENSE-MIT"))?.contains("John Doe\n"));
+        assert!(dir.join("LICENSE-APACHE").exists());
+        assert!(
+            fs::read_to_string(dir.join("Cargo.toml"))?.contains("license = \"MIT OR Apache-2.0\"")
+        );
+        assert!(fs::read_to_string(dir.join("package.json"))?
+            .contains("\"license\": \"MIT OR Apache-2.0\""));
+
+        assert!(applier.plan()?.outstanding()?.actions.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn config() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+        fs::create_dir(dir.join("docs"))?;
+
+        let config = Config {
+            docs_license: Some(String::from("CC-BY-4.0")),
+            copyright: Some(String::from("Copyright {{copyright_holders}} and {{org}}")),
+            vars: [(String::from("org"), String::from("ACME"))].into(),
+            ..Config::default()
+        };
+
+        let plan = Applier::new("MIT")
+            .dir(dir)
+            .author("John Doe")
+            .config(config)
+            .plan()?;
+        let paths = plan
+            .actions
+            .iter()
+            .map(|action| action.path().strip_prefix(dir).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, [Path::new("LICENSE"), Path::new("docs/LICENSE")]);
+        assert!(matches!(
+            &plan.actions[0],
+            Action::WriteFile { contents, .. } if contents.contains("Copyright John Doe and ACME\n")
+        ));
+
+        assert!(Applier::new("NOT A LICENSE").dir(dir).plan().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_authors() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+
+        assert!(Applier::new("MIT").dir(dir).plan().is_err());
+
+        fs::write(
+            dir.join("package.json"),
+            r#"{ "author": "Jane Doe <jd@example.com> (https://example.com)" }"#,
+        )?;
+        Applier::new("MIT").dir(dir).apply()?;
+        assert!(fs::read_to_string(dir.join("LICENSE"))?.contains(" Jane Doe\n"));
+
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"foo\"\nauthors = [\"John Doe <jd@example.com>\"]\n",
+        )?;
+        Applier::new("MIT").dir(dir).apply()?;
+        assert!(fs::read_to_string(dir.join("LICENSE"))?.contains(" John Doe\n"));
+
+        Ok(())
+    }
+}
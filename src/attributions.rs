@@ -0,0 +1,151 @@
+//! Attribution pages listing a project's dependencies, grouped by license.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::Dependency;
+
+/// The default name of the attributions page.
+pub const ATTRIBUTIONS_FILE: &str = "ATTRIBUTIONS.md";
+
+/// The handlebars template of the default attributions page, in Markdown.
+pub const ATTRIBUTIONS_TEMPLATE: &str = r#"# Third-party software
+
+This software uses the following third-party dependencies, grouped by license.
+{{#each licenses}}
+
+## {{{license}}}
+
+{{#each dependencies}}
+- {{{name}}} {{{version}}}
+{{/each}}
+{{#each texts}}
+
+```
+{{{this}}}
+```
+{{/each}}
+{{/each}}
+"#;
+
+/// The handlebars template of the attributions page as HTML, for pages whose name ends with
+/// `.html`.
+pub const ATTRIBUTIONS_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Third-party software</title>
+</head>
+<body>
+<h1>Third-party software</h1>
+<p>This software uses the following third-party dependencies, grouped by license.</p>
+{{#each licenses}}
+<h2>{{license}}</h2>
+<ul>
+{{#each dependencies}}
+<li>{{name}} {{version}}</li>
+{{/each}}
+</ul>
+{{#each texts}}
+<pre>{{this}}</pre>
+{{/each}}
+{{/each}}
+</body>
+</html>
+"#;
+
+/// The template context of an attributions page.
+#[derive(Debug, Serialize)]
+pub(crate) struct Attributions<'a> {
+    /// The dependencies, grouped by the license that they declare.
+    licenses: Vec<LicenseGroup<'a>>,
+
+    /// Every dependency, in order.
+    dependencies: &'a [Dependency],
+}
+
+/// The dependencies that declare the same license.
+#[derive(Debug, Serialize)]
+struct LicenseGroup<'a> {
+    /// The license expression, or "Unknown" for dependencies that don't declare one.
+    license: &'a str,
+
+    dependencies: Vec<&'a Dependency>,
+
+    /// The distinct license texts of the dependencies.
+    texts: Vec<&'a str>,
+}
+
+impl<'a> Attributions<'a> {
+    pub(crate) fn new(dependencies: &'a [Dependency]) -> Attributions<'a> {
+        let mut groups: BTreeMap<&str, Vec<&Dependency>> = BTreeMap::new();
+        for dependency in dependencies {
+            let license = dependency.license.as_deref().unwrap_or("Unknown");
+            groups.entry(license).or_default().push(dependency);
+        }
+
+        let licenses = groups
+            .into_iter()
+            .map(|(license, dependencies)| {
+                let mut texts: Vec<&str> = vec![];
+                for text in dependencies
+                    .iter()
+                    .flat_map(|dependency| dependency.license_texts.values())
+                {
+                    let text = text.trim_end();
+                    if !texts.contains(&text) {
+                        texts.push(text);
+                    }
+                }
+
+                LicenseGroup {
+                    license,
+                    dependencies,
+                    texts,
+                }
+            })
+            .collect();
+
+        Attributions {
+            licenses,
+            dependencies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Dependency, Renderer, ATTRIBUTIONS_HTML_TEMPLATE, ATTRIBUTIONS_TEMPLATE};
+
+    fn dependency(name: &str, license: Option<&str>, text: &str) -> Dependency {
+        let mut dependency = Dependency::new(name, "1.0.0", license.map(String::from));
+        dependency
+            .license_texts
+            .insert(String::from("LICENSE"), String::from(text));
+        dependency
+    }
+
+    #[test]
+    fn grouped_by_license() {
+        let dependencies = [
+            dependency("a", Some("MIT"), "MIT <text>\n"),
+            dependency("b", Some("Apache-2.0"), "Apache text\n"),
+            dependency("c", Some("MIT"), "MIT <text>"),
+            dependency("d", None, "custom text\n"),
+        ];
+
+        let page = Renderer::new()
+            .attributions(ATTRIBUTIONS_TEMPLATE, &dependencies)
+            .unwrap();
+        assert!(page.contains("## MIT\n\n- a 1.0.0\n- c 1.0.0\n"));
+        assert_eq!(page.matches("MIT <text>").count(), 1);
+        assert!(page.contains("## Unknown\n\n- d 1.0.0\n"));
+        assert!(page.find("## Apache-2.0").unwrap() < page.find("## MIT").unwrap());
+
+        let page = Renderer::new()
+            .attributions(ATTRIBUTIONS_HTML_TEMPLATE, &dependencies)
+            .unwrap();
+        assert!(page.contains("<pre>MIT &lt;text&gt;</pre>"));
+    }
+}
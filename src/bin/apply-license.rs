@@ -1,27 +1,1198 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use apply_license::{Config, Dependency, Expr, Journal, Plan, Renderer, Reverted, WalkOptions};
+use cargo_metadata::{DependencyKind, MetadataCommand};
+use clap::{Args, Parser, Subcommand};
+use log::{info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde_json::json;
+
+use crate::common::{
+    git, paint, ColorArgs, MinCommits, OutputArgs, RenderArgs, ResultExt, Stream, Style,
+    VerbosityArgs,
+};
+
+mod common;
 
 /// Apply open-source licenses to your project.
 #[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// The directory of the project to apply the license to. Defaults to the current directory.
+    #[clap(name = "PROJECT", parse(from_os_str), conflicts_with = "recursive")]
+    project: Option<PathBuf>,
+
     /// The authors of the crate. Can be specified multiple times.
     #[clap(long = "author", short = 'a', min_values = 1)]
     authors: Vec<String>,
 
-    /// The SPDX license expression for the license or licenses to apply.
-    #[clap(long = "license", short = 'l')]
+    /// Name the authors of the commits in the git history, following the repository's .mailmap,
+    /// instead of passing them with --author.
+    #[clap(long = "authors-from-git", conflicts_with = "authors")]
+    authors_from_git: bool,
+
+    /// With --authors-from-git, only name authors with at least this many commits, or this
+    /// percentage of all commits, such as `5` or `10%`.
+    #[clap(
+        long = "min-commits",
+        value_name = "N",
+        requires = "authors-from-git",
+        parse(try_from_str = common::parse_min_commits)
+    )]
+    min_commits: Option<MinCommits>,
+
+    /// The SPDX license expression for the license or licenses to apply, or `proprietary` for an
+    /// all-rights-reserved notice. Pass `-` to read it from stdin. If omitted, you'll be asked to
+    /// choose one.
+    #[clap(
+        long = "license",
+        short = 'l',
+        env = "APPLY_LICENSE_EXPR",
+        parse(from_str = apply_license::resolve_alias)
+    )]
+    license: Option<String>,
+
+    /// Write the license files to the root of the git repository, instead of the current
+    /// directory.
+    #[clap(long = "at-root")]
+    at_root: bool,
+
+    /// Apply the license to every project (a directory containing Cargo.toml, package.json, or
+    /// .git) under a directory, instead of the current directory.
+    #[clap(long = "recursive", value_name = "DIR", conflicts_with = "at-root")]
+    recursive: Option<PathBuf>,
+
+    /// A directory of vendored third-party code, such as `vendor/`. The license files of each
+    /// package in it are copied to LICENSES/third-party/, apart from the project's own license.
+    /// Can be specified multiple times.
+    #[clap(long = "third-party", value_name = "DIR")]
+    third_party: Vec<PathBuf>,
+
+    /// Warn if the license that GitHub detects for the repository, which is shown on its page,
+    /// differs from the license expression. Requires `curl`, and uses `GITHUB_TOKEN` if it's set.
+    #[clap(long = "remote-check")]
+    remote_check: bool,
+
+    /// An SPDX license expression for the documentation in docs/ (or the `docs_dir` in
+    /// .apply-license.toml), such as CC-BY-4.0, if it's licensed differently from the code.
+    #[clap(long = "docs-license", value_name = "EXPR")]
+    docs_license: Option<String>,
+
+    #[clap(flatten)]
+    render: RenderArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+
+    #[clap(flatten)]
+    color: ColorArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Set up licensing for a project in one step: write the license files, set the license in
+    /// Cargo.toml, add headers to source files, add a license section to README.md, and write a
+    /// NOTICE file if the license calls for one.
+    Init(InitArgs),
+
+    /// List the licenses that can be applied.
+    List(ListArgs),
+
+    /// List the license exceptions that can be applied with `WITH`.
+    ListExceptions(ListExceptionsArgs),
+
+    /// Revert the changes made by the last run in this directory.
+    Undo(UndoArgs),
+
+    /// Summarize what a license permits, requires, and limits.
+    Info(InfoArgs),
+
+    /// Identify the license in a file, such as an unlabeled `COPYING` file.
+    Detect(DetectArgs),
+
+    /// Show the differences between the texts of two licenses.
+    Diff(DiffArgs),
+
+    /// Rename license files with nonstandard names, such as `license.txt`, `LICENCE`, or
+    /// `MIT-LICENSE`, to `LICENSE` or `LICENSE-{id}`.
+    Migrate(MigrateArgs),
+
+    /// Add license headers to source files that don't have one.
+    Headers(HeadersArgs),
+
+    /// Print the license header for a file, without modifying it. Useful for editor snippets.
+    HeaderFor(HeaderForArgs),
+
+    /// Install a git pre-commit hook that checks that the license files are up to date.
+    InstallHook(InstallHookArgs),
+
+    /// Bundle the license texts of a cargo project's dependencies into THIRD-PARTY-LICENSES, for
+    /// software that must ship with attributions for its dependencies.
+    Thirdparty(ThirdpartyArgs),
+
+    /// Write a page crediting a cargo project's dependencies, grouped by license.
+    Attributions(AttributionsArgs),
+
+    /// Execute a plan saved from the output of `--dry-run --format json`, such as one that has
+    /// been reviewed and approved. Manifest changes in the plan are made without asking.
+    Apply(ApplyArgs),
+}
+
+#[derive(Debug, Args)]
+struct InitArgs {
+    /// The authors of the project. Can be specified multiple times.
+    #[clap(long = "author", short = 'a', min_values = 1)]
+    authors: Vec<String>,
+
+    /// The SPDX license expression for the license or licenses to apply, or `proprietary` for an
+    /// all-rights-reserved notice. Pass `-` to read it from stdin. If omitted, you'll be asked to
+    /// choose one.
+    #[clap(
+        long = "license",
+        short = 'l',
+        env = "APPLY_LICENSE_EXPR",
+        parse(from_str = apply_license::resolve_alias)
+    )]
+    license: Option<String>,
+
+    /// An SPDX license expression for the documentation in docs/ (or the `docs_dir` in
+    /// .apply-license.toml), such as CC-BY-4.0, if it's licensed differently from the code.
+    #[clap(long = "docs-license", value_name = "EXPR")]
+    docs_license: Option<String>,
+
+    #[clap(flatten)]
+    render: RenderArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct ListArgs {
+    /// Include every license in the SPDX license list, not just the supported ones.
+    #[clap(long = "all")]
+    all: bool,
+
+    /// Print the list as JSON.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct ListExceptionsArgs {
+    /// Print the list as JSON.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct UndoArgs {
+    /// The directory of the project to revert the last run in. Defaults to the current directory.
+    #[clap(name = "PROJECT", parse(from_os_str))]
+    project: Option<PathBuf>,
+
+    /// Revert files even if they have been modified since they were written.
+    #[clap(long = "force")]
+    force: bool,
+}
+
+#[derive(Debug, Args)]
+struct InfoArgs {
+    /// The SPDX identifier of the license, such as `Apache-2.0`.
+    id: String,
+
+    /// Print the summary as JSON.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct DetectArgs {
+    /// The file to identify.
+    path: PathBuf,
+
+    /// Print the result as JSON.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+#[derive(Debug, Args)]
+struct DiffArgs {
+    /// The SPDX identifier of the first license.
+    old: String,
+
+    /// The SPDX identifier of the second license.
+    new: String,
+}
+
+#[derive(Debug, Args)]
+struct MigrateArgs {
+    #[clap(flatten)]
+    output: OutputArgs,
+}
+
+/// Options for reading the dependencies of a cargo project.
+#[derive(Debug, Args)]
+struct DependencyArgs {
+    /// Path to Cargo.toml
+    #[clap(long = "manifest-path", value_name = "PATH")]
+    manifest_path: Option<PathBuf>,
+
+    /// Run without accessing the network. Passed through to `cargo metadata`.
+    #[clap(long = "offline")]
+    offline: bool,
+}
+
+#[derive(Debug, Args)]
+struct ThirdpartyArgs {
+    #[clap(flatten)]
+    dependencies: DependencyArgs,
+
+    /// The file to write the license texts to.
+    #[clap(
+        long = "output-file",
+        value_name = "FILE",
+        default_value = apply_license::THIRD_PARTY_LICENSES
+    )]
+    output_file: PathBuf,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct AttributionsArgs {
+    #[clap(flatten)]
+    dependencies: DependencyArgs,
+
+    /// The file to write the page to. If it ends with `.html`, the page is written as HTML.
+    #[clap(
+        long = "output-file",
+        value_name = "FILE",
+        default_value = apply_license::ATTRIBUTIONS_FILE
+    )]
+    output_file: PathBuf,
+
+    /// A handlebars template to render the page with, instead of the built-in one.
+    #[clap(long = "template", value_name = "FILE")]
+    template: Option<PathBuf>,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct ApplyArgs {
+    /// The plan to execute.
+    #[clap(long = "plan", value_name = "FILE")]
+    plan: PathBuf,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+struct HeadersArgs {
+    /// The directory containing the source files.
+    #[clap(default_value = ".")]
+    dir: PathBuf,
+
+    /// The authors named in the copyright line. Can be specified multiple times.
+    #[clap(long = "author", short = 'a', min_values = 1)]
+    authors: Vec<String>,
+
+    /// The SPDX license expression to declare in the headers. Pass `-` to read it from stdin.
+    #[clap(
+        long = "license",
+        short = 'l',
+        env = "APPLY_LICENSE_EXPR",
+        parse(from_str = apply_license::resolve_alias)
+    )]
     license: String,
+
+    /// Add headers to files in git submodules too, even though they usually have their own
+    /// licensing.
+    #[clap(long = "include-submodules")]
+    include_submodules: bool,
+
+    /// Keep running, and add headers to new source files as they are created.
+    #[clap(long = "watch", conflicts_with_all = &["dry-run", "check"])]
+    watch: bool,
+
+    #[clap(flatten)]
+    render: RenderArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
 }
 
-fn main() -> Result<()> {
+#[derive(Debug, Args)]
+struct HeaderForArgs {
+    /// The name of the file, which determines the comment style. The file doesn't need to exist.
+    file: PathBuf,
+
+    /// The authors named in the copyright line. Can be specified multiple times.
+    #[clap(long = "author", short = 'a', min_values = 1)]
+    authors: Vec<String>,
+
+    /// The SPDX license expression to declare in the header. Pass `-` to read it from stdin.
+    #[clap(
+        long = "license",
+        short = 'l',
+        env = "APPLY_LICENSE_EXPR",
+        parse(from_str = apply_license::resolve_alias)
+    )]
+    license: String,
+
+    #[clap(flatten)]
+    render: RenderArgs,
+}
+
+#[derive(Debug, Args)]
+struct InstallHookArgs {
+    /// The directory of the project to check in the hook. Defaults to the current directory.
+    #[clap(name = "PROJECT", parse(from_os_str))]
+    project: Option<PathBuf>,
+
+    /// Replace an existing pre-commit hook.
+    #[clap(long = "force")]
+    force: bool,
+
+    /// Arguments for `apply-license --check` in the hook, such as `-- -l MIT -a "Jane Doe"`. Cargo
+    /// projects don't need any, because they are checked with `cargo apply-license --check`.
+    #[clap(last = true)]
+    args: Vec<String>,
+}
+
+/// How long to wait after a source file is created before adding a header to it.
+const WATCH_DELAY: Duration = Duration::from_millis(500);
+
+/// Common licenses offered when no license is specified.
+const LICENSE_CHOICES: &[&str] = &["MIT", "Apache-2.0", "MIT OR Apache-2.0", "GPL-3.0"];
+
+/// Identifies pre-commit hooks written by `install-hook`.
+const HOOK_MARKER: &str = "# Installed by apply-license.";
+
+fn main() -> ExitCode {
     let args = Cli::parse();
+    args.color.init();
+    args.verbosity.init_logger();
 
-    let licenses = apply_license::parse_spdx(&args.license)?;
-    for (name, contents) in apply_license::render_license_text(&licenses, &args.authors)? {
-        fs::write(name, contents)?;
+    common::exit(match &args.command {
+        Some(Command::Init(init_args)) => init(init_args),
+        Some(Command::List(list_args)) => list(list_args),
+        Some(Command::ListExceptions(list_args)) => list_exceptions(list_args),
+        Some(Command::Undo(undo_args)) => undo(undo_args),
+        Some(Command::Info(info_args)) => info(info_args),
+        Some(Command::Detect(detect_args)) => detect(detect_args),
+        Some(Command::Diff(diff_args)) => diff(diff_args),
+        Some(Command::Migrate(migrate_args)) => migrate(migrate_args),
+        Some(Command::Headers(headers_args)) => headers(headers_args),
+        Some(Command::HeaderFor(header_args)) => header_for(header_args),
+        Some(Command::InstallHook(hook_args)) => install_hook(hook_args),
+        Some(Command::Thirdparty(thirdparty_args)) => thirdparty(thirdparty_args),
+        Some(Command::Attributions(attributions_args)) => attributions(attributions_args),
+        Some(Command::Apply(apply_args)) => apply_plan(apply_args),
+        None => apply(&args),
+    })
+}
+
+fn apply(args: &Cli) -> Result<ExitCode> {
+    let license = match &args.license {
+        Some(license) => common::read_license(license)?,
+        None => prompt_license()?,
+    };
+    let license = license.as_str();
+
+    let mut plan = Plan::new();
+    plan.license(license);
+
+    let project_dir = match &args.recursive {
+        Some(dir) => {
+            let projects = find_projects(dir)?;
+            if projects.is_empty() {
+                plan.warn(format!("no projects found in {}", dir.display()));
+            }
+
+            for project in projects {
+                info!("applying {} to {}", license, project.display());
+                plan_project(&mut plan, &project, &project, license, args)?;
+            }
+
+            dir.as_path()
+        }
+        None => {
+            let dir = args.project.as_deref().unwrap_or_else(|| Path::new(""));
+            if !dir.as_os_str().is_empty() && !dir.is_dir() {
+                return Err(anyhow!("{} is not a directory", dir.display())).config_err();
+            }
+
+            let output_dir = if args.at_root {
+                common::repository_root(dir)?
+            } else {
+                dir.to_owned()
+            };
+            plan_project(&mut plan, dir, &output_dir, license, args)?;
+
+            dir
+        }
+    };
+
+    common::run_plan(&plan, &args.output, project_dir)
+}
+
+/// Adds actions to the plan that apply the license to the project in `dir`, writing the license
+/// files to `output_dir`.
+fn plan_project(
+    plan: &mut Plan,
+    dir: &Path,
+    output_dir: &Path,
+    license: &str,
+    args: &Cli,
+) -> Result<()> {
+    let config = Config::load(dir).config_err()?;
+    let renderer =
+        args.render
+            .renderer(&config, dir, apply_license::project_name(dir).as_deref())?;
+
+    let authors = if args.authors_from_git {
+        common::git_authors(dir, args.min_commits)?
+    } else {
+        args.authors.clone()
+    };
+    let authors = common::resolve_authors(authors, dir, args.render.holder_style(&config))?;
+
+    apply_license::plan_license_files(plan, output_dir, &renderer, license, &authors)
+        .config_err()?;
+
+    apply_license::plan_path_licenses(plan, dir, &config, &renderer, &authors).config_err()?;
+
+    apply_license::plan_docs_license(
+        plan,
+        dir,
+        &config,
+        args.docs_license.as_deref(),
+        &renderer,
+        &authors,
+    )
+    .config_err()?;
+
+    common::plan_third_party_licenses(plan, dir, &config, &args.third_party)?;
+
+    plan.append(apply_license::plan_package_manifests(dir, license).config_err()?);
+
+    if args.remote_check {
+        common::plan_remote_check(plan, dir, license)?;
     }
 
     Ok(())
 }
+
+fn init(args: &InitArgs) -> Result<ExitCode> {
+    let license = match &args.license {
+        Some(license) => common::read_license(license)?,
+        None => prompt_license()?,
+    };
+    let license = license.as_str();
+
+    let dir = Path::new("");
+    let config = Config::load(dir).config_err()?;
+    let renderer =
+        args.render
+            .renderer(&config, dir, apply_license::project_name(dir).as_deref())?;
+
+    let authors =
+        common::resolve_authors(args.authors.clone(), dir, args.render.holder_style(&config))?;
+
+    let mut plan = Plan::new();
+    plan.license(license);
+
+    apply_license::plan_license_files(&mut plan, dir, &renderer, license, &authors).config_err()?;
+    apply_license::plan_path_licenses(&mut plan, dir, &config, &renderer, &authors).config_err()?;
+    apply_license::plan_docs_license(
+        &mut plan,
+        dir,
+        &config,
+        args.docs_license.as_deref(),
+        &renderer,
+        &authors,
+    )
+    .config_err()?;
+
+    let name = apply_license::plan_cargo_manifest(&mut plan, dir, license).config_err()?;
+    plan.append(apply_license::plan_package_manifests(dir, license).config_err()?);
+
+    let headers = apply_license::plan_headers(
+        Path::new("."),
+        &WalkOptions::new(),
+        &renderer,
+        license,
+        &authors,
+    )?;
+    // Leave the manifest alone if it's being updated, rather than overwriting the update.
+    let planned = plan
+        .actions
+        .iter()
+        .map(|action| action.path().strip_prefix(".").unwrap_or(action.path()))
+        .collect::<Vec<_>>();
+    let headers = headers
+        .actions
+        .into_iter()
+        .filter(|action| {
+            let path = action.path();
+            !planned.contains(&path.strip_prefix(".").unwrap_or(path))
+        })
+        .collect::<Vec<_>>();
+    plan.actions.extend(headers);
+
+    let readme = Path::new(README);
+    if !readme.exists() {
+        plan.warn(format!(
+            "there is no {}, so no license section was added",
+            README
+        ));
+    } else {
+        let contents = fs::read_to_string(readme)
+            .with_context(|| format!("could not read {}", readme.display()))?;
+        if !apply_license::has_license_section(&contents) {
+            let mut section = apply_license::readme_section(license).config_err()?;
+            if let Some(docs_license) = args.docs_license.as_ref().or(config.docs_license.as_ref())
+            {
+                let paragraph =
+                    apply_license::readme_docs_paragraph(docs_license, config.docs_dir())
+                        .config_err()?;
+                section.push('\n');
+                section.push_str(&paragraph);
+            }
+            let separator = if contents.ends_with("\n\n") || contents.is_empty() {
+                ""
+            } else if contents.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            plan.write_file(readme, format!("{}{}{}", contents, separator, section));
+        }
+    }
+
+    let needs_notice = apply_license::parse_spdx(license)
+        .config_err()?
+        .iter()
+        .any(|license| NOTICE_LICENSES.contains(&license.spdx.as_str()));
+    let notice = Path::new(NOTICE);
+    if needs_notice && !notice.exists() {
+        let name = match name {
+            Some(name) => name,
+            None => env::current_dir()?
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        let copyright = renderer.copyright_line(&authors).config_err()?;
+        plan.write_file(notice, format!("{}\n{}\n", name, copyright));
+    }
+
+    common::run_plan(&plan, &args.output, Path::new(""))
+}
+
+/// The README that `init` adds a license section to.
+const README: &str = "README.md";
+
+/// The file that `init` writes for licenses that call for attribution notices.
+const NOTICE: &str = "NOTICE";
+
+/// Licenses whose terms refer to a `NOTICE` file.
+const NOTICE_LICENSES: &[&str] = &["Apache-2.0"];
+
+/// Files that mark a directory as the root of a project.
+const PROJECT_MARKERS: &[&str] = &["Cargo.toml", "package.json", ".git"];
+
+/// Finds the projects in `dir`, without descending into the projects themselves or hidden
+/// directories.
+fn find_projects(dir: &Path) -> Result<Vec<PathBuf>> {
+    if PROJECT_MARKERS
+        .iter()
+        .any(|marker| dir.join(marker).exists())
+    {
+        return Ok(vec![dir.to_owned()]);
+    }
+
+    let mut projects = vec![];
+
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("could not read {}", dir.display()))
+        .config_err()?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type()?.is_dir() {
+            projects.extend(find_projects(&entry.path())?);
+        }
+    }
+
+    Ok(projects)
+}
+
+/// Asks the user to choose a license expression on the terminal.
+fn prompt_license() -> Result<String> {
+    if !io::stdin().is_terminal() {
+        return Err(anyhow!("no license specified (pass --license)")).config_err();
+    }
+
+    eprintln!("Which license would you like to apply?");
+    for (i, choice) in LICENSE_CHOICES.iter().enumerate() {
+        eprintln!("  {}) {}", i + 1, choice);
+    }
+
+    loop {
+        eprint!("Enter a number or an SPDX license expression: ");
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            return Err(anyhow!("no license specified")).config_err();
+        }
+
+        let answer = answer.trim();
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=LICENSE_CHOICES.len()).contains(&n) => {
+                return Ok(LICENSE_CHOICES[n - 1].to_owned())
+            }
+            Ok(_) => eprintln!("Please choose a number from the list."),
+            Err(_) if answer.is_empty() => (),
+            Err(_) => return Ok(apply_license::resolve_alias(answer)),
+        }
+    }
+}
+
+fn list(args: &ListArgs) -> Result<ExitCode> {
+    let licenses = apply_license::spdx_licenses()
+        .map(|license| {
+            let supported = apply_license::supported_license(&license.id);
+            (license, supported)
+        })
+        .filter(|(_, supported)| args.all || supported.is_some())
+        .collect::<Vec<_>>();
+
+    if args.json {
+        let licenses = licenses
+            .iter()
+            .map(|(license, supported)| {
+                json!({
+                    "id": license.id,
+                    "name": license.name,
+                    "osi_approved": license.is_osi_approved,
+                    "supported": supported.is_some(),
+                    "metadata": supported.map(|supported| &supported.metadata),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!("{}", serde_json::to_string_pretty(&licenses)?);
+    } else {
+        let width = licenses
+            .iter()
+            .map(|(license, _)| license.id.len())
+            .max()
+            .unwrap_or_default();
+
+        for (license, supported) in licenses {
+            let note = if supported.is_some() {
+                ""
+            } else {
+                " (unsupported)"
+            };
+            println!(
+                "{:width$}  {}{}",
+                license.id,
+                license.name,
+                note,
+                width = width
+            );
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn list_exceptions(args: &ListExceptionsArgs) -> Result<ExitCode> {
+    let exceptions = apply_license::supported_exceptions().collect::<Vec<_>>();
+
+    if args.json {
+        let exceptions = exceptions
+            .iter()
+            .map(|exception| {
+                json!({
+                    "id": exception.spdx,
+                    "licenses": exception.licenses,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!("{}", serde_json::to_string_pretty(&exceptions)?);
+    } else {
+        let width = exceptions
+            .iter()
+            .map(|exception| exception.spdx.len())
+            .max()
+            .unwrap_or_default();
+
+        for exception in exceptions {
+            println!(
+                "{:width$}  usually with {}",
+                exception.spdx,
+                exception.licenses.join(", "),
+                width = width
+            );
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn undo(args: &UndoArgs) -> Result<ExitCode> {
+    let dir = args.project.as_deref().unwrap_or_else(|| Path::new(""));
+    let journal = Journal::load(dir)?
+        .ok_or_else(|| anyhow!("nothing to undo"))
+        .config_err()?;
+
+    let mut complete = true;
+    journal.undo(args.force, |reverted| match reverted {
+        Reverted::Restored(path) => println!("Restored {}", path.display()),
+        Reverted::Removed(path) => println!("Removed {}", path.display()),
+        Reverted::Modified(path) => {
+            complete = false;
+            warn!(
+                "{} has been modified since it was written, so it was not reverted \
+                 (pass --force to revert it anyway)",
+                path.display()
+            );
+        }
+    })?;
+
+    if complete {
+        Journal::remove(dir)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn info(args: &InfoArgs) -> Result<ExitCode> {
+    let license = apply_license::spdx_license(&args.id)
+        .ok_or_else(|| anyhow!("'{}' is not in the SPDX license list", args.id))
+        .config_err()?;
+    let supported = apply_license::supported_license(&license.id);
+    let metadata = supported.map(|supported| &supported.metadata);
+
+    let mut links = vec![format!("https://spdx.org/licenses/{}.html", license.id)];
+    // choosealicense.com names licenses by their base id, such as `gpl-3.0` for
+    // `GPL-3.0-or-later`.
+    if let Some(supported) = supported {
+        links.push(format!(
+            "https://choosealicense.com/licenses/{}/",
+            supported.spdx.to_lowercase()
+        ));
+    }
+
+    if args.json {
+        let info = json!({
+            "id": license.id,
+            "name": license.name,
+            "osi_approved": license.is_osi_approved,
+            "deprecated": license.is_deprecated_license_id,
+            "metadata": metadata,
+            "links": links,
+        });
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let yes_no = |b| if b { "yes" } else { "no" };
+    println!("{}: {}", license.id, license.name);
+    println!("OSI approved: {}", yes_no(license.is_osi_approved));
+    if license.is_deprecated_license_id {
+        println!("Deprecated: yes");
+    }
+
+    match metadata {
+        Some(metadata) => {
+            println!("Copyleft: {}", metadata.copyleft);
+            print_list("Permissions", Style::Green, &metadata.permissions);
+            print_list("Conditions", Style::Cyan, &metadata.conditions);
+            print_list("Limitations", Style::Red, &metadata.limitations);
+        }
+        None => println!("\nThis license is not supported, so there is no summary of its terms."),
+    }
+
+    println!();
+    for link in links {
+        println!("{}", link);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints a heading followed by a bulleted list.
+fn print_list<T: fmt::Display>(heading: &str, style: Style, items: &[T]) {
+    println!("\n{}:", heading);
+    for item in items {
+        println!("  {} {}", paint(Stream::Stdout, style, "-"), item);
+    }
+}
+
+fn detect(args: &DetectArgs) -> Result<ExitCode> {
+    let text = fs::read_to_string(&args.path)
+        .with_context(|| format!("could not read {}", args.path.display()))?;
+
+    let detection = apply_license::detect_license(&text)
+        .ok_or_else(|| anyhow!("could not identify the license in {}", args.path.display()))
+        .config_err()?;
+
+    if args.json {
+        let detection = json!({
+            "path": args.path,
+            "license": detection.license.spdx,
+            "confidence": detection.confidence,
+        });
+        println!("{}", serde_json::to_string_pretty(&detection)?);
+    } else {
+        println!(
+            "{} ({:.0}% confidence)",
+            detection.license.spdx,
+            detection.confidence * 100.0
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(args: &DiffArgs) -> Result<ExitCode> {
+    let render = |id: &str| -> Result<String> {
+        let license = match apply_license::parse_spdx(id).config_err()?[..] {
+            [license] => license,
+            _ => return Err(anyhow!("expected a single license, not {}", id)).config_err(),
+        };
+
+        // Show where the copyright holders go, rather than any names in particular.
+        let files = apply_license::render_license_text(&[license], &["<copyright holders>"])?;
+        Ok(files.into_values().next().unwrap())
+    };
+
+    let old = render(&args.old)?;
+    let new = render(&args.new)?;
+    common::print_diff(&args.old, &old, &args.new, &new);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn migrate(args: &MigrateArgs) -> Result<ExitCode> {
+    let plan = apply_license::migrate_license_files(Path::new(""))?;
+    common::run_plan(&plan, &args.output, Path::new(""))
+}
+
+fn apply_plan(args: &ApplyArgs) -> Result<ExitCode> {
+    let plan = read_plan(&args.plan).config_err()?;
+
+    // The plan was already approved, so its manifest changes don't need to be confirmed again.
+    let output = OutputArgs {
+        yes: true,
+        ..args.output.clone()
+    };
+    common::run_plan(&plan, &output, Path::new(""))
+}
+
+/// Reads a plan saved from the JSON output of a dry run, or a bare plan.
+fn read_plan(path: &Path) -> Result<Plan> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let mut value: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse {}", path.display()))?;
+
+    if let Some(plan) = value.get_mut("plan") {
+        value = plan.take();
+    }
+
+    serde_json::from_value(value).with_context(|| format!("{} is not a valid plan", path.display()))
+}
+
+fn thirdparty(args: &ThirdpartyArgs) -> Result<ExitCode> {
+    let dependencies = cargo_dependencies(&args.dependencies)?;
+
+    let mut plan = Plan::new();
+    plan.write_file(
+        &args.output_file,
+        apply_license::third_party_licenses(&dependencies),
+    );
+
+    for dependency in &dependencies {
+        if dependency.license_texts.is_empty() {
+            plan.warn(format!(
+                "could not find a license file for {} {}",
+                dependency.name, dependency.version
+            ));
+        }
+    }
+
+    common::run_plan(&plan, &args.output, Path::new(""))
+}
+
+fn attributions(args: &AttributionsArgs) -> Result<ExitCode> {
+    let template = match &args.template {
+        Some(path) => fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))
+            .config_err()?,
+        None if args
+            .output_file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html")) =>
+        {
+            String::from(apply_license::ATTRIBUTIONS_HTML_TEMPLATE)
+        }
+        None => String::from(apply_license::ATTRIBUTIONS_TEMPLATE),
+    };
+
+    let dependencies = cargo_dependencies(&args.dependencies)?;
+    let page = Renderer::new()
+        .attributions(&template, &dependencies)
+        .config_err()?;
+
+    let mut plan = Plan::new();
+    plan.write_file(&args.output_file, page);
+    common::run_plan(&plan, &args.output, Path::new(""))
+}
+
+/// Returns the dependencies that are built into a cargo project, with the license texts from their
+/// sources, sorted by name and version. Dev-dependencies and build dependencies aren't distributed
+/// with the project, so they're left out.
+fn cargo_dependencies(args: &DependencyArgs) -> Result<Vec<Dependency>> {
+    let mut metadata_cmd = MetadataCommand::new();
+    if let Some(manifest_path) = &args.manifest_path {
+        metadata_cmd.manifest_path(manifest_path);
+    }
+    if args.offline {
+        metadata_cmd.other_options(vec![String::from("--offline")]);
+    }
+
+    let metadata = metadata_cmd
+        .exec()
+        .context("unable to parse cargo metadata")
+        .config_err()?;
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| anyhow!("cargo metadata did not resolve the dependencies"))?;
+
+    let nodes = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<HashMap<_, _>>();
+
+    let mut reachable = HashSet::new();
+    let mut queue = metadata.workspace_members.iter().collect::<Vec<_>>();
+    while let Some(id) = queue.pop() {
+        let node = match nodes.get(id) {
+            Some(node) => node,
+            None => continue,
+        };
+
+        for dep in &node.deps {
+            let normal = dep
+                .dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal);
+            if normal && reachable.insert(&dep.pkg) {
+                queue.push(&dep.pkg);
+            }
+        }
+    }
+
+    let mut packages = metadata
+        .packages
+        .iter()
+        .filter(|package| {
+            reachable.contains(&package.id) && !metadata.workspace_members.contains(&package.id)
+        })
+        .collect::<Vec<_>>();
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    let mut dependencies = vec![];
+    for package in packages {
+        let mut dependency = Dependency::new(
+            &package.name,
+            package.version.to_string(),
+            package.license.clone(),
+        );
+        if let Some(dir) = package.manifest_path.parent() {
+            dependency.read_license_files(
+                dir.as_std_path(),
+                package.license_file.as_ref().map(|file| file.as_std_path()),
+            )?;
+        }
+        dependencies.push(dependency);
+    }
+
+    Ok(dependencies)
+}
+
+fn headers(args: &HeadersArgs) -> Result<ExitCode> {
+    let license = common::read_license(&args.license)?;
+    Expr::parse(&license).config_err()?;
+
+    let config = Config::load(&args.dir).config_err()?;
+    let renderer = args.render.renderer(
+        &config,
+        &args.dir,
+        apply_license::project_name(&args.dir).as_deref(),
+    )?;
+    let authors = common::resolve_authors(
+        args.authors.clone(),
+        &args.dir,
+        args.render.holder_style(&config),
+    )?;
+    let mut options = WalkOptions::new();
+    options.include_submodules(args.include_submodules);
+    let plan_headers = || -> Result<Plan> {
+        let mut plan =
+            apply_license::plan_headers(&args.dir, &options, &renderer, &license, &authors)?;
+        plan.license(&license);
+        Ok(plan)
+    };
+
+    let code = common::run_plan(&plan_headers()?, &args.output, &args.dir)?;
+    if !args.watch {
+        return Ok(code);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&args.dir, RecursiveMode::Recursive)?;
+    info!("watching {} for new source files", args.dir.display());
+
+    for event in &rx {
+        let event = event?;
+        if !matches!(event.kind, EventKind::Create(_))
+            || !event
+                .paths
+                .iter()
+                .any(|path| apply_license::comment_style(path).is_some())
+        {
+            continue;
+        }
+
+        // Give the file a chance to be written, and pick up any other files created with it.
+        thread::sleep(WATCH_DELAY);
+        while rx.try_recv().is_ok() {}
+
+        let plan = plan_headers()?;
+        if !plan.actions.is_empty() {
+            common::run_plan(&plan, &args.output, &args.dir)?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn header_for(args: &HeaderForArgs) -> Result<ExitCode> {
+    let license = common::read_license(&args.license)?;
+    Expr::parse(&license).config_err()?;
+
+    let style = apply_license::comment_style(&args.file)
+        .ok_or_else(|| anyhow!("unknown comment style for {}", args.file.display()))
+        .config_err()?;
+
+    let dir = Path::new("");
+    let config = Config::load(dir).config_err()?;
+    let authors =
+        common::resolve_authors(args.authors.clone(), dir, args.render.holder_style(&config))?;
+    let header = args
+        .render
+        .renderer(&config, dir, apply_license::project_name(dir).as_deref())?
+        .header_for(&args.file, &license, &authors, &style)
+        .config_err()?;
+    print!("{}", header);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn install_hook(args: &InstallHookArgs) -> Result<ExitCode> {
+    let dir = apply_license::existing_dir(args.project.as_deref().unwrap_or(Path::new("")));
+    let hooks_dir = git::git(dir, ["rev-parse", "--git-path", "hooks"])
+        .context("not in a git repository")
+        .config_err()?;
+    let hook = dir.join(hooks_dir.trim()).join("pre-commit");
+
+    // Hooks run in the root of the repository, so the project is checked by its path from there.
+    let prefix = git::git(dir, ["rev-parse", "--show-prefix"])?;
+    let prefix = prefix.trim();
+
+    if hook.exists() && !args.force && !fs::read_to_string(&hook)?.contains(HOOK_MARKER) {
+        return Err(anyhow!(
+            "a pre-commit hook already exists at {} (pass --force to replace it)",
+            hook.display()
+        ))
+        .config_err();
+    }
+
+    let command = if args.args.is_empty() {
+        if !dir.join("Cargo.toml").exists() {
+            return Err(anyhow!(
+                "pass the arguments to check with, such as \
+                 `apply-license install-hook -- -l MIT -a \"Jane Doe\"`"
+            ))
+            .config_err();
+        }
+
+        iter::once(String::from("cargo apply-license --check"))
+            .chain((!prefix.is_empty()).then(|| shell_quote(prefix)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        iter::once(String::from("apply-license --check"))
+            .chain((!prefix.is_empty()).then(|| shell_quote(prefix)))
+            .chain(args.args.iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    fs::create_dir_all(hook.parent().unwrap())?;
+    fs::write(
+        &hook,
+        format!("#!/bin/sh\n{}\n\nexec {}\n", HOOK_MARKER, command),
+    )
+    .with_context(|| format!("could not write {}", hook.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+    }
+
+    println!("Installed pre-commit hook at {}", hook.display());
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Quotes an argument for use in a POSIX shell script.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
@@ -1,13 +1,25 @@
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 use anyhow::{anyhow, Context, Result};
-use cargo_metadata::MetadataCommand;
-use clap::{Args, Parser};
-use toml_edit::{value, Document};
+use apply_license::{Config, Expr, Plan, WalkOptions};
+use cargo_metadata::{MetadataCommand, Package};
+use clap::{ArgEnum, Args, Parser};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::{debug, info, warn};
+use toml_edit::{Document, Item};
+
+use crate::common::{ColorArgs, MinCommits, OutputArgs, RenderArgs, ResultExt, VerbosityArgs};
+
+mod common;
 
 static DEFAULT_LICENSE: &str = "MIT OR Apache-2.0";
 
+/// How closely a `license-file` must match a supported license to be converted.
+const MIN_LICENSE_FILE_CONFIDENCE: f64 = 0.9;
+
 /// Apply open-source licenses to your cargo project.
 ///
 /// Parses author and license information from your Cargo.toml.
@@ -19,19 +31,159 @@ enum Cli {
 
 #[derive(Debug, Args)]
 #[clap(override_usage = "\
-    cargo apply-license")]
+    cargo apply-license [PROJECT]")]
 struct ApplyLicenseArgs {
+    /// The directory of the package, or the path to its Cargo.toml. Defaults to the current
+    /// directory.
+    #[clap(name = "PROJECT", parse(from_os_str), conflicts_with = "PATH")]
+    project: Option<PathBuf>,
+
     /// Path to Cargo.toml
     #[clap(long = "manifest-path", name = "PATH")]
     manifest_path: Option<PathBuf>,
 
-    /// An SPDX license expression. If specified, overrides the value in Cargo.toml.
-    #[clap(long = "license")]
+    /// An SPDX license expression, or `proprietary` for an all-rights-reserved notice. Pass `-`
+    /// to read it from stdin. Only used if Cargo.toml doesn't specify a license.
+    #[clap(
+        long = "license",
+        env = "APPLY_LICENSE_EXPR",
+        parse(from_str = apply_license::resolve_alias)
+    )]
     license: Option<String>,
+
+    /// The authors to name in the copyright line, instead of the authors in Cargo.toml. Can be
+    /// specified multiple times.
+    #[clap(long = "author", short = 'a', value_name = "AUTHOR")]
+    authors: Vec<String>,
+
+    /// Name the authors given with --author in addition to the authors in Cargo.toml, rather than
+    /// instead of them.
+    #[clap(long = "append-authors", requires = "authors")]
+    append_authors: bool,
+
+    /// Name the authors of the commits in the git history, following the repository's .mailmap,
+    /// instead of the authors in Cargo.toml.
+    #[clap(long = "authors-from-git", conflicts_with = "authors")]
+    authors_from_git: bool,
+
+    /// With --authors-from-git, only name authors with at least this many commits, or this
+    /// percentage of all commits, such as `5` or `10%`.
+    #[clap(
+        long = "min-commits",
+        value_name = "N",
+        requires = "authors-from-git",
+        parse(try_from_str = common::parse_min_commits)
+    )]
+    min_commits: Option<MinCommits>,
+
+    /// An SPDX license expression for the documentation in docs/ (or the `docs_dir` in
+    /// .apply-license.toml), such as CC-BY-4.0, if it's licensed differently from the code.
+    #[clap(long = "docs-license", value_name = "EXPR")]
+    docs_license: Option<String>,
+
+    /// Apply the default license, MIT OR Apache-2.0, without asking if Cargo.toml doesn't
+    /// specify a license.
+    #[clap(long = "default-license", conflicts_with = "license")]
+    default_license: bool,
+
+    /// What to do if Cargo.toml points to the license text with `license-file` instead of
+    /// specifying a license. If not specified, asks whether to convert it.
+    #[clap(long = "license-file", arg_enum, name = "ACTION")]
+    license_file: Option<LicenseFileAction>,
+
+    /// Only write the license files, never editing Cargo.toml, such as when it's managed by
+    /// another tool.
+    #[clap(long = "no-manifest-update", conflicts_with = "ACTION")]
+    no_manifest_update: bool,
+
+    /// The directory to write the license files to. Defaults to the directory containing
+    /// Cargo.toml.
+    #[clap(long = "output-dir", name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Write the license files to the root of the git repository, such as for a crate in a
+    /// monorepo with a single top-level license.
+    #[clap(long = "at-root", conflicts_with = "DIR")]
+    at_root: bool,
+
+    /// Apply licenses to every member of the workspace, using each member's own license field
+    /// and writing the license files next to its Cargo.toml.
+    #[clap(long = "workspace", conflicts_with_all = &["DIR", "at-root"])]
+    workspace: bool,
+
+    /// Skip workspace members whose name or directory matches a glob, such as `examples/*` or
+    /// `*-fuzz`. Can be specified multiple times.
+    #[clap(long = "exclude-member", value_name = "GLOB", requires = "workspace")]
+    exclude_members: Vec<String>,
+
+    /// A directory of vendored third-party code, such as `vendor/`. The license files of each
+    /// package in it are copied to LICENSES/third-party/, apart from the crate's own license. Can
+    /// be specified multiple times.
+    #[clap(long = "third-party", value_name = "DIR")]
+    third_party: Vec<PathBuf>,
+
+    /// Warn if the license that GitHub detects for the repository, which is shown on its page,
+    /// differs from the license expression. Requires `curl`, and uses `GITHUB_TOKEN` if it's set.
+    #[clap(long = "remote-check")]
+    remote_check: bool,
+
+    /// Run without accessing the network. Passed through to `cargo metadata`.
+    #[clap(long = "offline")]
+    offline: bool,
+
+    /// Require Cargo.lock to be up to date. Passed through to `cargo metadata`.
+    #[clap(long = "locked")]
+    locked: bool,
+
+    /// Require Cargo.lock to be up to date, without accessing the network. Passed through to
+    /// `cargo metadata`.
+    #[clap(long = "frozen")]
+    frozen: bool,
+
+    #[clap(flatten)]
+    render: RenderArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+
+    #[clap(flatten)]
+    color: ColorArgs,
 }
 
-fn main() -> Result<()> {
+/// What to do with a `license-file` in the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+enum LicenseFileAction {
+    /// Leave the manifest and the license file as they are.
+    Keep,
+
+    /// Replace `license-file` with the SPDX identifier of the license it contains, and generate
+    /// standard license files.
+    Convert,
+}
+
+fn main() -> ExitCode {
     let Cli::ApplyLicense(args) = Cli::parse();
+    args.color.init();
+    args.verbosity.init_logger();
+
+    common::exit(run(args))
+}
+
+fn run(mut args: ApplyLicenseArgs) -> Result<ExitCode> {
+    if let Some(license) = &args.license {
+        args.license = Some(common::read_license(license)?);
+    }
+
+    if let Some(project) = args.project.take() {
+        args.manifest_path = Some(if project.is_dir() {
+            project.join("Cargo.toml")
+        } else {
+            project
+        });
+    }
 
     let mut metadata_cmd = MetadataCommand::new();
 
@@ -39,46 +191,531 @@ fn main() -> Result<()> {
         metadata_cmd.manifest_path(manifest_path);
     }
 
+    let cargo_flags = [
+        ("--offline", args.offline),
+        ("--locked", args.locked),
+        ("--frozen", args.frozen),
+    ];
+    metadata_cmd.other_options(
+        cargo_flags
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(flag, _)| flag.to_string())
+            .collect::<Vec<_>>(),
+    );
+
     let metadata = metadata_cmd
         .exec()
-        .context("unable to parse cargo metadata")?;
+        .context("unable to parse cargo metadata")
+        .config_err()?;
+
+    let mut plan = Plan::new();
+
+    let project_dir = if args.workspace {
+        let excluded = exclude_member_globs(&args.exclude_members)?;
+
+        let mut licenses = vec![];
+        let members = metadata
+            .packages
+            .iter()
+            .filter(|package| metadata.workspace_members.contains(&package.id));
+        for package in members {
+            let manifest_path = relative_to_current_dir(package.manifest_path.as_std_path());
+
+            let member_dir = package
+                .manifest_path
+                .parent()
+                .and_then(|dir| dir.strip_prefix(&metadata.workspace_root).ok())
+                .map(|dir| dir.as_std_path())
+                .unwrap_or_else(|| Path::new(""));
+            if excluded.is_match(&package.name) || excluded.is_match(member_dir) {
+                info!("skipping excluded member {}", package.name);
+                continue;
+            }
+            if let Some(license_expr) = plan_package(&args, package, &manifest_path, &mut plan)? {
+                licenses.push(license_expr);
+            }
+        }
+
+        // The plan can only describe a single license, so leave it out if the members differ.
+        licenses.dedup();
+        if let [license_expr] = &licenses[..] {
+            plan.license(license_expr.clone());
+        }
+
+        relative_to_current_dir(metadata.workspace_root.as_std_path())
+    } else {
+        let package = metadata
+            .root_package()
+            .ok_or_else(|| anyhow!("root package not found"))
+            .config_err()?;
+        let manifest_path = args
+            .manifest_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+        if let Some(license_expr) = plan_package(&args, package, &manifest_path, &mut plan)? {
+            plan.license(license_expr);
+        }
+
+        manifest_path
+            .parent()
+            .map(Path::to_owned)
+            .unwrap_or_default()
+    };
+
+    if args.remote_check {
+        match plan.license.clone() {
+            Some(license_expr) => common::plan_remote_check(
+                &mut plan,
+                metadata.workspace_root.as_std_path(),
+                &license_expr,
+            )?,
+            None => {
+                plan.warn("skipping --remote-check, since no single license is being applied");
+            }
+        }
+    }
+
+    common::run_plan(&plan, &args.output, &project_dir)
+}
+
+/// Adds actions to the plan that license a single package, returning the license expression that
+/// was applied, or `None` if the package is left as it is.
+fn plan_package(
+    args: &ApplyLicenseArgs,
+    package: &Package,
+    manifest_path: &Path,
+    plan: &mut Plan,
+) -> Result<Option<String>> {
+    // Paths are relative to the manifest, unless the license files should go somewhere else.
+    let project_dir = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+    let config = Config::load(project_dir).config_err()?;
+
+    // Members may inherit the authors from `[workspace.package]` with `authors.workspace = true`,
+    // which cargo has already resolved.
+    let authors = if args.authors_from_git {
+        common::git_authors(project_dir, args.min_commits)?
+    } else if args.authors.is_empty() {
+        package.authors.clone()
+    } else if args.append_authors {
+        package
+            .authors
+            .iter()
+            .chain(&args.authors)
+            .cloned()
+            .collect()
+    } else {
+        args.authors.clone()
+    };
+    let authors = common::resolve_authors(authors, project_dir, args.render.holder_style(&config))?;
+    let authors = authors.iter().map(String::as_str).collect::<Vec<_>>();
+    let names = if authors.is_empty() {
+        vec![]
+    } else {
+        apply_license::parse_author_names(&authors).config_err()?
+    };
+    debug!(
+        "copyright holders of {}: {}",
+        package.name,
+        names.join(", ")
+    );
+
+    let manifest: Document = fs::read_to_string(manifest_path)
+        .with_context(|| format!("could not read {}", manifest_path.display()))?
+        .parse()
+        .with_context(|| format!("could not parse {}", manifest_path.display()))
+        .config_err()?;
+
+    let license_item = manifest
+        .get("package")
+        .and_then(|package| package.get("license"));
+
+    // Members may inherit the license from the workspace, which cargo has already resolved.
+    let original_license = match license_item {
+        Some(item) if item.get("workspace").and_then(Item::as_bool) == Some(true) => {
+            package.license.as_deref()
+        }
+        item => item.and_then(Item::as_str),
+    };
+
+    let license_file = manifest
+        .get("package")
+        .and_then(|package| package.get("license-file"))
+        .and_then(Item::as_str);
+
+    let chosen_license = match (original_license, args.license.as_deref()) {
+        // An invalid license in the manifest is replaced, rather than rendered.
+        (Some(original_license), Some(license)) if Expr::parse(original_license).is_err() => {
+            Some(license)
+        }
+        (Some(original_license), Some(license)) if original_license != license => {
+            warn!(
+                "ignoring --license {}, since {} already specifies license = \"{}\"",
+                license,
+                manifest_path.display(),
+                original_license
+            );
+            Some(original_license)
+        }
+        (original_license, license) => original_license.or(license),
+    };
 
-    let authors = &metadata
-        .root_package()
-        .ok_or_else(|| anyhow!("root package not found"))?
-        .authors;
-    let authors = authors
+    // `license-file` is only replaced when converting it was chosen, either with
+    // `--license-file convert` or when asked.
+    let convert = !args.no_manifest_update
+        && license_file.is_some()
+        && (args.license_file == Some(LicenseFileAction::Convert) || chosen_license.is_none());
+
+    let license_expr = match (chosen_license, license_file) {
+        (Some(license_expr), _) => license_expr,
+        // Converting to `license` would mean editing the manifest.
+        (None, Some(license_file)) if args.no_manifest_update => {
+            info!(
+                "keeping license-file {} (pass --license to choose a license)",
+                license_file
+            );
+            return Ok(None);
+        }
+        (None, Some(license_file)) => {
+            match convert_license_file(args, &project_dir.join(license_file))? {
+                Some(license_expr) => license_expr,
+                None => return Ok(None),
+            }
+        }
+        (None, None) => default_license(args, manifest_path)?,
+    };
+    info!(
+        "applying license expression {} to {}",
+        license_expr, package.name
+    );
+
+    let renderer = args
+        .render
+        .renderer(&config, project_dir, Some(&package.name))?;
+
+    let output_dir = match &args.output_dir {
+        Some(output_dir) => output_dir.clone(),
+        None if args.at_root => common::repository_root(project_dir)?,
+        None => project_dir.to_owned(),
+    };
+    apply_license::plan_license_files(plan, &output_dir, &renderer, license_expr, &names)
+        .config_err()?;
+
+    apply_license::plan_path_licenses(plan, project_dir, &config, &renderer, &names)
+        .config_err()?;
+
+    apply_license::plan_docs_license(
+        plan,
+        project_dir,
+        &config,
+        args.docs_license.as_deref(),
+        &renderer,
+        &names,
+    )
+    .config_err()?;
+
+    common::plan_third_party_licenses(plan, project_dir, &config, &args.third_party)?;
+
+    if args.no_manifest_update {
+        if original_license != Some(license_expr) {
+            info!(
+                "not setting package.license = \"{}\" in {} (--no-manifest-update)",
+                license_expr,
+                manifest_path.display()
+            );
+        }
+    } else if original_license != Some(license_expr) {
+        plan.update_manifest(manifest_path, "package.license", license_expr);
+    }
+
+    // Once `license` is set, the file that `license-file` pointed to is superseded by the
+    // generated license files.
+    if let (true, Some(license_file)) = (convert, license_file) {
+        plan.remove_manifest_field(manifest_path, "package.license-file");
+
+        let license_file = project_dir.join(license_file);
+        if license_file.exists()
+            && !plan
+                .actions
+                .iter()
+                .any(|action| action.path() == license_file)
+        {
+            plan.remove_file(license_file);
+        }
+    }
+
+    // What crates.io will see once the plan is executed.
+    let published_license = if args.no_manifest_update {
+        original_license
+    } else {
+        Some(license_expr)
+    };
+    check_publishable(
+        &manifest,
+        published_license,
+        license_file.is_some() && !convert,
+        plan,
+    );
+
+    if args.output.check {
+        check_header_licenses(args, project_dir, &config, license_expr, plan)?;
+    }
+
+    Ok(Some(license_expr.to_owned()))
+}
+
+/// Reports a problem for each source file in the package whose `SPDX-License-Identifier` header
+/// declares a license that isn't part of `license_expr`, or of the license of their directory in
+/// the configuration. Vendored code and nested packages are skipped, since they have licenses of
+/// their own.
+fn check_header_licenses(
+    args: &ApplyLicenseArgs,
+    project_dir: &Path,
+    config: &Config,
+    license_expr: &str,
+    plan: &mut Plan,
+) -> Result<()> {
+    let dir = apply_license::existing_dir(project_dir);
+
+    // The most specific directory applies, so check the longest paths first.
+    let mut path_licenses = config
+        .paths
         .iter()
-        .map(|author| author.as_str())
+        .map(|(path, license)| (path.as_path(), license.as_str()))
         .collect::<Vec<_>>();
-    let names = apply_license::parse_author_names(&authors)?;
-
-    let manifest_path = args
-        .manifest_path
-        .as_ref()
-        .map(Path::new)
-        .unwrap_or_else(|| Path::new("Cargo.toml"));
-    let mut manifest: Document = fs::read_to_string(manifest_path)?.parse()?;
-
-    let (original_license, licenses) = {
-        let license_value = &mut manifest["package"]["license"];
-        let original_license = license_value.as_str().map(ToOwned::to_owned);
-
-        let license_expr = args
-            .license
-            .unwrap_or_else(|| String::from(DEFAULT_LICENSE));
-        let license_value = license_value.or_insert(value(license_expr));
-        let licenses = apply_license::parse_spdx(license_value.as_str().unwrap())?;
-        (original_license, licenses)
+    if let Some(docs_license) = args.docs_license.as_ref().or(config.docs_license.as_ref()) {
+        path_licenses.push((config.docs_dir(), docs_license));
+    }
+    path_licenses.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+
+    for path in apply_license::source_files(dir, &WalkOptions::new())? {
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+
+        let vendored = args
+            .third_party
+            .iter()
+            .chain(&config.third_party)
+            .any(|third_party| relative.starts_with(third_party));
+        let nested = relative
+            .ancestors()
+            .skip(1)
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .any(|ancestor| dir.join(ancestor).join("Cargo.toml").exists());
+        if vendored || nested {
+            continue;
+        }
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+        let file_expr = match apply_license::header_license(&contents) {
+            Some(file_expr) => file_expr,
+            None => continue,
+        };
+
+        let display = project_dir.join(relative);
+        let declared = match Expr::parse(file_expr) {
+            Ok(expr) => expr,
+            Err(_) => {
+                plan.problem(format!(
+                    "{} has an invalid SPDX-License-Identifier: {}",
+                    display.display(),
+                    file_expr
+                ));
+                continue;
+            }
+        };
+
+        let expected = path_licenses
+            .iter()
+            .find(|(path, _)| relative.starts_with(path))
+            .map_or(license_expr, |(_, license)| license);
+        let expected_licenses = Expr::parse(expected).config_err()?;
+        let expected_licenses = expected_licenses.licenses();
+        if !declared
+            .licenses()
+            .iter()
+            .all(|license| expected_licenses.contains(license))
+        {
+            plan.problem(format!(
+                "{} declares SPDX-License-Identifier: {}, which is not part of {}",
+                display.display(),
+                file_expr,
+                expected
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compiles the `--exclude-member` patterns.
+fn exclude_member_globs(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(
+            Glob::new(pattern)
+                .with_context(|| format!("invalid --exclude-member pattern {}", pattern))
+                .config_err()?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
+/// Returns `path` relative to the current directory if it's inside it, so that messages and
+/// reports show short paths.
+fn relative_to_current_dir(path: &Path) -> PathBuf {
+    env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path)
+        .to_owned()
+}
+
+/// Returns the default license expression if the user agrees to it, for a manifest that doesn't
+/// specify a license.
+fn default_license(args: &ApplyLicenseArgs, manifest_path: &Path) -> Result<&'static str> {
+    if args.default_license {
+        return Ok(DEFAULT_LICENSE);
+    }
+
+    warn!(
+        "{} does not specify a license, so the default license is {}",
+        manifest_path.display(),
+        DEFAULT_LICENSE
+    );
+
+    // Nothing is modified in dry runs and checks, so there's no need to ask.
+    if args.output.dry_run
+        || args.output.check
+        || common::confirm(&format!("Apply {}?", DEFAULT_LICENSE))?
+    {
+        return Ok(DEFAULT_LICENSE);
+    }
+
+    Err(anyhow!(
+        "no license specified (pass --license to choose one, or --default-license to apply {})",
+        DEFAULT_LICENSE
+    ))
+    .config_err()
+}
+
+/// Identifies the license in the file that the manifest's `license-file` points to, and returns
+/// its SPDX identifier if the manifest should be converted to use it, or `None` to keep the file.
+fn convert_license_file(args: &ApplyLicenseArgs, path: &Path) -> Result<Option<&'static str>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("could not read license-file {}", path.display()))
+        .config_err()?;
+
+    let detection = apply_license::detect_license(&text)
+        .filter(|detection| detection.confidence >= MIN_LICENSE_FILE_CONFIDENCE);
+
+    let license = match (detection, args.license_file) {
+        (None, Some(LicenseFileAction::Convert)) => {
+            return Err(anyhow!(
+                "could not identify the license in {} (pass --license to choose one)",
+                path.display()
+            ))
+            .config_err();
+        }
+        (None, _) => {
+            info!(
+                "keeping license-file {}, which isn't a supported license",
+                path.display()
+            );
+            return Ok(None);
+        }
+        (Some(detection), _) => detection.license.spdx.as_str(),
     };
 
-    for (name, contents) in apply_license::render_license_text(&licenses, &names)? {
-        fs::write(name, contents)?;
+    let convert = match args.license_file {
+        Some(action) => action == LicenseFileAction::Convert,
+        None => {
+            warn!(
+                "Cargo.toml points to {} with `license-file`, which looks like {}",
+                path.display(),
+                license
+            );
+
+            // Nothing is modified in dry runs and checks, so there's no need to ask.
+            !args.output.dry_run
+                && !args.output.check
+                && common::confirm(&format!(
+                    "Replace it with `license = \"{}\"` and generated license files?",
+                    license
+                ))?
+        }
+    };
+
+    if convert {
+        Ok(Some(license))
+    } else {
+        info!(
+            "keeping license-file {} (pass `--license-file convert` to replace it)",
+            path.display()
+        );
+        Ok(None)
     }
+}
 
-    if original_license.as_deref() != manifest["package"]["license"].as_str() {
-        fs::write(manifest_path, manifest.to_string())?;
+/// Warns about anything in the license fields that `cargo publish` or crates.io would complain
+/// about, once `package.license` is `license` and `package.license-file` is set or not, according
+/// to `license_file`.
+fn check_publishable(
+    manifest: &Document,
+    license: Option<&str>,
+    license_file: bool,
+    plan: &mut Plan,
+) {
+    if manifest["package"].get("publish").and_then(Item::as_bool) == Some(false) {
+        return;
     }
 
-    Ok(())
+    let license_expr = match (license, license_file) {
+        (None, false) => {
+            plan.warn(
+                "neither `license` nor `license-file` is set in the manifest, so crates.io will \
+                 reject the crate",
+            );
+            return;
+        }
+        (None, true) => return,
+        (Some(license_expr), license_file) => {
+            if license_file {
+                plan.warn(
+                    "both `license` and `license-file` are set in the manifest, but only one is \
+                     needed (crates.io will ignore `license-file`)",
+                );
+            }
+            license_expr
+        }
+    };
+
+    let expr = match Expr::parse(license_expr) {
+        Ok(expr) => expr,
+        Err(_) => {
+            plan.warn(format!(
+                "license = \"{}\" is not a valid SPDX license expression, so crates.io will \
+                 reject the crate",
+                license_expr
+            ));
+            return;
+        }
+    };
+
+    if license_expr.contains('/') {
+        plan.warn(format!(
+            "crates.io has deprecated `/` in license expressions, use \"{}\" instead",
+            expr
+        ));
+    }
+
+    if expr.licenses().contains(&apply_license::PROPRIETARY) {
+        plan.warn(format!(
+            "{} is not an open-source license, so set `publish = false` to keep the crate from \
+             being published to crates.io",
+            apply_license::PROPRIETARY
+        ));
+    }
 }
@@ -0,0 +1,96 @@
+//! Terminal colors.
+
+use std::env;
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::{ArgEnum, Args};
+
+static STDOUT_COLOR: AtomicBool = AtomicBool::new(false);
+static STDERR_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// When to use colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum ColorChoice {
+    /// Use colors if the output is a terminal and `NO_COLOR` is not set.
+    Auto,
+
+    /// Always use colors.
+    Always,
+
+    /// Never use colors.
+    Never,
+}
+
+/// Options controlling colored output.
+#[derive(Debug, Args)]
+pub struct ColorArgs {
+    /// When to use colors. `auto` respects the `NO_COLOR` environment variable.
+    #[clap(
+        long = "color",
+        arg_enum,
+        default_value = "auto",
+        value_name = "WHEN",
+        global = true
+    )]
+    color: ColorChoice,
+}
+
+impl ColorArgs {
+    /// Determines whether stdout and stderr should be colored.
+    pub fn init(&self) {
+        let enabled = |is_terminal: bool| match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = matches!(env::var_os("NO_COLOR"), Some(value) if !value.is_empty());
+                is_terminal && !no_color
+            }
+        };
+
+        STDOUT_COLOR.store(enabled(io::stdout().is_terminal()), Ordering::Relaxed);
+        STDERR_COLOR.store(enabled(io::stderr().is_terminal()), Ordering::Relaxed);
+    }
+}
+
+/// An output stream.
+#[derive(Debug, Clone, Copy)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// A text style.
+#[derive(Debug, Clone, Copy)]
+pub enum Style {
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Red => "31",
+            Style::Green => "32",
+            Style::Yellow => "33",
+            Style::Cyan => "36",
+        }
+    }
+}
+
+/// Wraps `text` in the escape codes for `style`, if colors are enabled for `stream`.
+pub fn paint(stream: Stream, style: Style, text: impl Display) -> String {
+    let enabled = match stream {
+        Stream::Stdout => &STDOUT_COLOR,
+        Stream::Stderr => &STDERR_COLOR,
+    };
+
+    if enabled.load(Ordering::Relaxed) {
+        format!("\x1b[{}m{}\x1b[0m", style.code(), text)
+    } else {
+        text.to_string()
+    }
+}
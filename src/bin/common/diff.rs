@@ -0,0 +1,47 @@
+//! Rendering of colored diffs.
+
+use similar::{ChangeTag, TextDiff};
+
+use super::{paint, Stream, Style};
+
+/// Prints a unified diff between `old` and `new` to stdout, in the style of `git diff`.
+pub fn print_diff(old_name: &str, old: &str, new_name: &str, new: &str) {
+    let diff = TextDiff::from_lines(old, new);
+
+    println!(
+        "{}",
+        paint(Stream::Stdout, Style::Red, format!("--- {}", old_name))
+    );
+    println!(
+        "{}",
+        paint(Stream::Stdout, Style::Green, format!("+++ {}", new_name))
+    );
+
+    for hunk in diff.unified_diff().iter_hunks() {
+        println!("{}", paint(Stream::Stdout, Style::Cyan, hunk.header()));
+
+        for change in hunk.iter_changes() {
+            let line = change.value().trim_end_matches('\n');
+
+            match change.tag() {
+                ChangeTag::Equal => println!(" {}", line),
+                ChangeTag::Delete => {
+                    println!(
+                        "{}",
+                        paint(Stream::Stdout, Style::Red, format!("-{}", line))
+                    )
+                }
+                ChangeTag::Insert => {
+                    println!(
+                        "{}",
+                        paint(Stream::Stdout, Style::Green, format!("+{}", line))
+                    )
+                }
+            }
+
+            if change.missing_newline() {
+                println!("\\ No newline at end of file");
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Interaction with git.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+/// Runs git with the given arguments in `dir`, returning its stdout.
+pub fn git<I, S>(dir: &Path, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("could not run git")?;
+
+    if !output.status.success() {
+        bail!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Returns the names of the authors of the commits to `dir`, with the number of commits by each,
+/// from the most commits to the fewest. If `dir` is the root of the repository, every commit
+/// counts, even one that doesn't change any files.
+///
+/// Authors are mapped through the repository's `.mailmap`, so that a contributor who committed
+/// under several names or email addresses is only named once, by their canonical name.
+pub fn history_authors(dir: &Path) -> Result<Vec<(String, usize)>> {
+    // `shortlog` reads from stdin unless it's given a revision.
+    let mut args = vec!["shortlog", "--summary", "--numbered", "HEAD"];
+    if !git(dir, ["rev-parse", "--show-prefix"])?.trim().is_empty() {
+        args.extend(["--", "."]);
+    }
+    let shortlog = git(dir, args)?;
+
+    shortlog
+        .lines()
+        .filter_map(|line| line.trim().split_once('\t'))
+        .map(|(commits, name)| {
+            let commits = commits
+                .trim()
+                .parse()
+                .with_context(|| format!("unexpected output from git shortlog: {}", commits))?;
+            Ok((name.trim().to_owned(), commits))
+        })
+        .collect()
+}
+
+/// Returns the canonical name of the author `name <email>` according to the repository's
+/// `.mailmap`, which is `name` if the mailmap doesn't mention them.
+pub fn mailmap_name(dir: &Path, name: &str, email: &str) -> Result<String> {
+    let mapped = git(dir, ["check-mailmap", &format!("{} <{}>", name, email)])?;
+    Ok(apply_license::Author::parse(mapped.trim()).name.to_owned())
+}
+
+/// Stages the given files and commits them in the repository containing `dir`, leaving any other
+/// staged changes alone.
+pub fn commit(dir: &Path, paths: &[PathBuf], message: &str) -> Result<()> {
+    let base = env::current_dir()?.join(dir);
+    let dir = apply_license::existing_dir(dir);
+
+    // git runs in `dir`, so the paths are made relative to it.
+    let paths = paths
+        .iter()
+        .map(|path| path.strip_prefix(&base).unwrap_or(path))
+        .collect::<Vec<_>>();
+
+    git(
+        dir,
+        // `--all` stages files that were removed.
+        ["add", "--all", "--"]
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(paths.iter().map(|p| p.as_os_str())),
+    )
+    .context("could not stage changes")?;
+
+    git(
+        dir,
+        ["commit", "--quiet", "-m", message, "--"]
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(paths.iter().map(|p| p.as_os_str())),
+    )
+    .context("could not create commit")?;
+
+    Ok(())
+}
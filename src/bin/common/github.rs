@@ -0,0 +1,75 @@
+//! GitHub Actions [workflow commands] for annotating files in pull requests.
+//!
+//! [workflow commands]: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+
+use std::fmt;
+use std::path::Path;
+
+/// The severity of an annotation.
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Notice,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Level::Notice => "notice",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        })
+    }
+}
+
+/// Prints an annotation for `file` (and optionally a line in it) to stdout.
+pub fn annotate(level: Level, file: Option<&Path>, line: Option<usize>, message: &str) {
+    let mut properties = vec![];
+
+    if let Some(file) = file {
+        properties.push(format!(
+            "file={}",
+            escape_property(&file.display().to_string())
+        ));
+    }
+
+    if let Some(line) = line {
+        properties.push(format!("line={}", line));
+    }
+
+    println!(
+        "::{} {}::{}",
+        level,
+        properties.join(","),
+        escape_data(message)
+    );
+}
+
+/// Returns the 1-based number of the first line that differs between `old` and `new`.
+pub fn first_changed_line(old: &str, new: &str) -> usize {
+    let mut old_lines = old.lines();
+    let mut new_lines = new.lines();
+
+    let mut line = 1;
+    while let (Some(old), Some(new)) = (old_lines.next(), new_lines.next()) {
+        if old != new {
+            break;
+        }
+        line += 1;
+    }
+
+    line
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
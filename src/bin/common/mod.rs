@@ -0,0 +1,956 @@
+//! Command-line functionality shared by the `apply-license` and `cargo-apply-license` binaries.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Context, Result};
+use apply_license::{
+    existing_dir, Action, Config, CopyrightSymbol, Event, ExecuteOptions, FileNameStyle,
+    FileStatus, HolderStyle, Plan, Renderer, Report, UserConfig,
+};
+use chrono::{Datelike, TimeZone, Utc};
+use clap::{ArgEnum, Args};
+use log::{debug, info, warn, Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use toml_edit::Document;
+
+pub use self::color::{paint, ColorArgs, Stream, Style};
+pub use self::diff::print_diff;
+pub use self::remote::plan_remote_check;
+
+pub mod git;
+
+mod color;
+mod diff;
+mod github;
+mod remote;
+
+/// Exit status when `--check` finds changes that need to be made.
+pub const EXIT_CHANGES_NEEDED: u8 = 1;
+
+/// Exit status when the arguments, manifest, or license expression are invalid.
+pub const EXIT_INVALID_CONFIG: u8 = 2;
+
+/// Exit status for any other error.
+pub const EXIT_INTERNAL_ERROR: u8 = 3;
+
+/// An error caused by invalid arguments, manifests, or license expressions, rather than by a
+/// problem with the tool or the filesystem.
+#[derive(Debug)]
+pub struct ConfigError(anyhow::Error);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Extension trait for marking errors as configuration errors.
+pub trait ResultExt<T> {
+    /// Marks the error, if any, as a [`ConfigError`].
+    fn config_err(self) -> Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> ResultExt<T> for Result<T, E> {
+    fn config_err(self) -> Result<T> {
+        self.map_err(|e| ConfigError(e.into()).into())
+    }
+}
+
+/// Converts the result of a run into the process exit status, printing the error if any.
+pub fn exit(result: Result<ExitCode>) -> ExitCode {
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{} {:?}", paint(Stream::Stderr, Style::Red, "Error:"), e);
+
+            if e.chain().any(|cause| cause.is::<ConfigError>()) {
+                ExitCode::from(EXIT_INVALID_CONFIG)
+            } else {
+                ExitCode::from(EXIT_INTERNAL_ERROR)
+            }
+        }
+    }
+}
+
+/// How to present the results of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum Format {
+    /// Human-readable text.
+    Human,
+
+    /// A JSON description of everything the run did, or would do.
+    Json,
+
+    /// GitHub Actions workflow commands that annotate the files that need to change.
+    Github,
+}
+
+/// Options controlling how changes are performed and reported.
+#[derive(Debug, Clone, Args)]
+pub struct OutputArgs {
+    /// Print what would be changed without modifying any files.
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Check that the project is up to date without modifying any files. Exits with status 1 if
+    /// changes are needed, 2 if the configuration is invalid, and 3 for any other error.
+    #[clap(long = "check")]
+    pub check: bool,
+
+    /// Copy each existing file to `<file>.bak` before modifying it.
+    #[clap(long = "backup")]
+    pub backup: bool,
+
+    /// Stage the changed files and commit them to git.
+    #[clap(long = "commit", conflicts_with_all = &["dry-run", "check"])]
+    pub commit: bool,
+
+    /// Don't ask for confirmation before modifying manifests.
+    #[clap(long = "yes", short = 'y')]
+    pub yes: bool,
+
+    /// The output format.
+    #[clap(long = "format", arg_enum, default_value = "human")]
+    pub format: Format,
+
+    /// Print the SHA-256 checksum of each file that is written, in the format of `sha256sum`.
+    /// Checksums are always included in JSON output.
+    #[clap(long = "checksums")]
+    pub checksums: bool,
+}
+
+/// How to write the copyright symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum SymbolArg {
+    /// ©
+    Unicode,
+
+    /// (c)
+    Ascii,
+}
+
+impl From<SymbolArg> for CopyrightSymbol {
+    fn from(symbol: SymbolArg) -> CopyrightSymbol {
+        match symbol {
+            SymbolArg::Unicode => CopyrightSymbol::Unicode,
+            SymbolArg::Ascii => CopyrightSymbol::Ascii,
+        }
+    }
+}
+
+/// Who to name in copyright lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum HolderStyleArg {
+    /// The authors, separated by commas.
+    Authors,
+
+    /// "The {{project}} Contributors", instead of listing individual authors.
+    Contributors,
+}
+
+impl From<HolderStyleArg> for HolderStyle {
+    fn from(style: HolderStyleArg) -> HolderStyle {
+        match style {
+            HolderStyleArg::Authors => HolderStyle::Authors,
+            HolderStyleArg::Contributors => HolderStyle::Contributors,
+        }
+    }
+}
+
+/// How to name license files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum FileNameStyleArg {
+    /// `LICENSE`, or `LICENSE-{id}` for each of several licenses.
+    Standard,
+
+    /// `COPYING` for the GPL and AGPL, and `COPYING.LESSER` for the LGPL, as GNU recommends.
+    Gnu,
+}
+
+impl From<FileNameStyleArg> for FileNameStyle {
+    fn from(style: FileNameStyleArg) -> FileNameStyle {
+        match style {
+            FileNameStyleArg::Standard => FileNameStyle::Standard,
+            FileNameStyleArg::Gnu => FileNameStyle::Gnu,
+        }
+    }
+}
+
+/// Options controlling how the license text is rendered.
+#[derive(Debug, Args)]
+pub struct RenderArgs {
+    /// A handlebars template of the copyright line, such as
+    /// "Copyright {{year}} {{copyright_holders}}. All rights reserved." Overrides the value in
+    /// .apply-license.toml.
+    #[clap(long = "copyright-format", value_name = "FORMAT")]
+    pub copyright_format: Option<String>,
+
+    /// How to write the copyright symbol in copyright lines. Overrides the value in
+    /// .apply-license.toml.
+    #[clap(long = "copyright-symbol", arg_enum, value_name = "SYMBOL")]
+    pub copyright_symbol: Option<SymbolArg>,
+
+    /// Who to name in copyright lines. The contributors style names the project instead of the
+    /// authors. Overrides the value in .apply-license.toml.
+    #[clap(long = "holder-style", arg_enum, value_name = "STYLE")]
+    pub holder_style: Option<HolderStyleArg>,
+
+    /// Sort the authors in copyright lines alphabetically, instead of naming them in the order
+    /// that they're given in.
+    #[clap(long = "sort-authors")]
+    pub sort_authors: bool,
+
+    /// Strip whitespace from the end of each line of the generated files.
+    #[clap(long = "trim-trailing-whitespace")]
+    pub trim_trailing_whitespace: bool,
+
+    /// Ensure that the generated files end with exactly one newline.
+    #[clap(long = "insert-final-newline")]
+    pub insert_final_newline: bool,
+
+    /// Leave the year out of copyright lines, as in "Copyright (c) John Doe".
+    #[clap(long = "no-year")]
+    pub no_year: bool,
+
+    /// Produce the same files on every platform: sort the authors, write `\n` line endings, and
+    /// take the year from SOURCE_DATE_EPOCH or the last git commit instead of the clock.
+    #[clap(long = "deterministic")]
+    pub deterministic: bool,
+
+    /// End each license file with a line saying that it was generated by apply-license, with the
+    /// SPDX identifier, so that future maintainers know it's safe to regenerate.
+    #[clap(long = "provenance")]
+    pub provenance: bool,
+
+    /// The convention to name license files by. Overrides the value in .apply-license.toml.
+    #[clap(long = "file-name-style", arg_enum, value_name = "STYLE")]
+    pub file_name_style: Option<FileNameStyleArg>,
+
+    /// Add a variable to the template context, such as `--var company="Example Inc."`, for custom
+    /// copyright formats and templates. Can be specified multiple times.
+    #[clap(long = "var", value_name = "KEY=VALUE", parse(try_from_str = parse_var))]
+    pub vars: Vec<(String, String)>,
+}
+
+/// Parses a `--var` argument.
+fn parse_var(var: &str) -> Result<(String, String), String> {
+    match var.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!("expected KEY=VALUE, not {:?}", var)),
+    }
+}
+
+impl RenderArgs {
+    /// Creates a renderer configured by the arguments, falling back to the project configuration.
+    ///
+    /// The `project` variable is set to the name of the project, if any, unless it's overridden
+    /// by a variable in the configuration or on the command line. In deterministic mode, the year
+    /// may be taken from the git history of the project in `dir`.
+    pub fn renderer(&self, config: &Config, dir: &Path, project: Option<&str>) -> Result<Renderer> {
+        let mut renderer = Renderer::new();
+
+        if let Some(project) = project {
+            renderer.var("project", project);
+        }
+
+        renderer.configure(config);
+
+        // The command line overrides the configuration.
+        if let Some(format) = &self.copyright_format {
+            renderer.copyright(format);
+        }
+
+        if let Some(symbol) = self.copyright_symbol {
+            renderer.copyright_symbol(symbol.into());
+        }
+
+        renderer.holder_style(self.holder_style(config));
+
+        renderer
+            .trim_trailing_whitespace(
+                self.trim_trailing_whitespace || config.trim_trailing_whitespace,
+            )
+            .insert_final_newline(self.insert_final_newline || config.insert_final_newline)
+            .provenance(self.provenance || config.provenance)
+            .no_year(self.no_year || config.no_year)
+            .sort_authors(self.sort_authors || config.sort_authors);
+
+        if let Some(style) = self.file_name_style {
+            renderer.file_name_style(style.into());
+        }
+
+        for (key, value) in &self.vars {
+            renderer.var(key, value.as_str());
+        }
+
+        if self.deterministic || config.deterministic {
+            renderer.deterministic(true).year(deterministic_year(dir)?);
+        }
+
+        Ok(renderer)
+    }
+
+    /// Returns who to name in copyright lines, falling back to the project configuration.
+    pub fn holder_style(&self, config: &Config) -> HolderStyle {
+        self.holder_style
+            .map(Into::into)
+            .or(config.holder_style)
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the authors to name in copyright lines, which are `authors`, without duplicates, unless
+/// there are none and the holder style names the authors.
+///
+/// Otherwise, the authors are taken from the user configuration, or from git's `user.name` in
+/// `dir`. If neither is set, the user is asked who holds the copyright, and may save the answer
+/// to the user configuration for next time.
+pub fn resolve_authors(
+    authors: Vec<String>,
+    dir: &Path,
+    holder_style: HolderStyle,
+) -> Result<Vec<String>> {
+    if !authors.is_empty() || holder_style != HolderStyle::Authors {
+        let authors = authors.iter().map(String::as_str).collect::<Vec<_>>();
+        return Ok(apply_license::dedup_authors(&authors)
+            .into_iter()
+            .map(str::to_owned)
+            .collect());
+    }
+
+    let user_config_path = UserConfig::path();
+    if let Some(path) = &user_config_path {
+        let user_config = UserConfig::load(path).config_err()?;
+        if !user_config.authors.is_empty() {
+            debug!("using the authors in {}", path.display());
+            return Ok(user_config.authors);
+        }
+    }
+
+    let dir = existing_dir(dir);
+    if let Ok(name) = git::git(dir, ["config", "user.name"]) {
+        let name = name.trim();
+        if !name.is_empty() {
+            debug!("using git's user.name as the author");
+
+            // The same person may be known by another name in the project's history.
+            let name = git::git(dir, ["config", "user.email"])
+                .and_then(|email| git::mailmap_name(dir, name, email.trim()))
+                .unwrap_or_else(|_| name.to_owned());
+            return Ok(vec![name]);
+        }
+    }
+
+    let author = prompt("Who holds the copyright?")?
+        .ok_or_else(|| anyhow!("no authors found (pass --author)"))
+        .config_err()?;
+
+    if let Some(path) = &user_config_path {
+        if confirm(&format!(
+            "Save {} as your default author in {}?",
+            author,
+            path.display()
+        ))? {
+            let mut user_config = UserConfig::load(path).config_err()?;
+            user_config.authors = vec![author.clone()];
+            user_config.save(path)?;
+        }
+    }
+
+    Ok(vec![author])
+}
+
+/// How many commits an author needs in the git history to be named with `--authors-from-git`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinCommits {
+    /// A number of commits, such as `--min-commits 5`.
+    Count(usize),
+
+    /// A percentage of all commits, such as `--min-commits 10%`.
+    Percent(f64),
+}
+
+/// Parses a `--min-commits` argument.
+pub fn parse_min_commits(min_commits: &str) -> Result<MinCommits, String> {
+    let parsed = match min_commits.strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse()
+            .ok()
+            .filter(|percent| (0.0..=100.0).contains(percent))
+            .map(MinCommits::Percent),
+        None => min_commits.trim().parse().ok().map(MinCommits::Count),
+    };
+
+    parsed.ok_or_else(|| {
+        format!(
+            "expected a number of commits or a percentage, not {:?}",
+            min_commits
+        )
+    })
+}
+
+/// Returns the names of the authors in the git history of `dir`, for `--authors-from-git`, leaving
+/// out those with fewer commits than `min_commits`.
+pub fn git_authors(dir: &Path, min_commits: Option<MinCommits>) -> Result<Vec<String>> {
+    let dir = existing_dir(dir);
+
+    let history = git::history_authors(dir)
+        .context("could not read the git history")
+        .config_err()?;
+    let total: usize = history.iter().map(|(_, commits)| commits).sum();
+
+    let mut authors = vec![];
+    for (author, commits) in history {
+        let enough = match min_commits {
+            None => true,
+            Some(MinCommits::Count(min)) => commits >= min,
+            Some(MinCommits::Percent(percent)) => commits as f64 * 100.0 >= percent * total as f64,
+        };
+
+        if enough {
+            authors.push(author);
+        } else {
+            debug!("leaving out {}, with only {} commits", author, commits);
+        }
+    }
+
+    debug!("authors in the git history: {}", authors.join(", "));
+    Ok(authors)
+}
+
+/// Returns the license expression given with `--license`, reading it from stdin if it's `-`.
+pub fn read_license(license: &str) -> Result<String> {
+    if license != "-" {
+        return Ok(license.to_owned());
+    }
+
+    let mut license = String::new();
+    io::stdin()
+        .read_to_string(&mut license)
+        .context("could not read the license expression from stdin")?;
+
+    match license.trim() {
+        "" => Err(anyhow!("no license expression on stdin")).config_err(),
+        license => Ok(apply_license::resolve_alias(license)),
+    }
+}
+
+/// Returns the year to put in copyright lines in deterministic mode: the year of
+/// `SOURCE_DATE_EPOCH` if it's set, as in [reproducible builds][sde], or otherwise the year of the
+/// last git commit in the repository containing `dir`.
+///
+/// [sde]: https://reproducible-builds.org/docs/source-date-epoch/
+fn deterministic_year(dir: &Path) -> Result<i32> {
+    let timestamp = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(timestamp) => timestamp,
+        Err(_) => git::git(existing_dir(dir), ["log", "-1", "--format=%ct"])
+            .context(
+                "--deterministic needs SOURCE_DATE_EPOCH or a git commit to take the year from",
+            )
+            .config_err()?,
+    };
+
+    let timestamp = timestamp
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid timestamp {:?}", timestamp.trim()))
+        .config_err()?;
+
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .map(|date| date.year())
+        .ok_or_else(|| anyhow!("invalid timestamp {}", timestamp))
+        .config_err()
+}
+
+/// Options controlling how much is logged to stderr.
+#[derive(Debug, Args)]
+pub struct VerbosityArgs {
+    /// Print more information about what is being done. Pass twice for debugging output.
+    #[clap(long = "verbose", short = 'v', parse(from_occurrences), global = true)]
+    verbose: u64,
+
+    /// Only print errors.
+    #[clap(long = "quiet", short = 'q', conflicts_with = "verbose", global = true)]
+    quiet: bool,
+}
+
+impl VerbosityArgs {
+    /// Installs a logger that prints messages at the requested verbosity to stderr.
+    pub fn init_logger(&self) {
+        static LOGGER: Logger = Logger;
+
+        let level = match (self.quiet, self.verbose) {
+            (true, _) => LevelFilter::Error,
+            (false, 0) => LevelFilter::Warn,
+            (false, 1) => LevelFilter::Info,
+            (false, _) => LevelFilter::Debug,
+        };
+
+        log::set_logger(&LOGGER).expect("logger already initialized");
+        log::set_max_level(level);
+    }
+}
+
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Only show messages from this package, not its dependencies.
+        metadata.level() <= log::max_level()
+            && ["apply_license", "cargo_apply_license"]
+                .iter()
+                .any(|krate| metadata.target().split("::").next() == Some(krate))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let (level, style) = match record.level() {
+            Level::Error => ("error", Style::Red),
+            Level::Warn => ("warning", Style::Yellow),
+            Level::Info => ("info", Style::Green),
+            Level::Debug => ("debug", Style::Cyan),
+            Level::Trace => ("trace", Style::Cyan),
+        };
+
+        eprintln!("{}: {}", paint(Stream::Stderr, style, level), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Finds the root of the git repository containing `dir`, by looking for `.git`.
+///
+/// The path is relative to the current directory if the current directory is inside the
+/// repository.
+pub fn repository_root(dir: &Path) -> Result<PathBuf> {
+    let start = existing_dir(dir);
+    let start = start
+        .canonicalize()
+        .with_context(|| format!("could not find {}", start.display()))?;
+
+    let root = start
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .ok_or_else(|| anyhow!("{} is not in a git repository", start.display()))
+        .config_err()?;
+
+    let cwd = env::current_dir()?.canonicalize()?;
+    match cwd.strip_prefix(root) {
+        Ok(relative) => Ok(relative.components().map(|_| "..").collect()),
+        Err(_) => Ok(root.to_owned()),
+    }
+}
+
+/// Adds actions to the plan that collect the license files of the vendored code in each of the
+/// `dirs` of the project in `root`, and in the directories listed in the configuration, into
+/// [`apply_license::THIRD_PARTY_DIR`].
+pub fn plan_third_party_licenses(
+    plan: &mut Plan,
+    root: &Path,
+    config: &Config,
+    dirs: &[PathBuf],
+) -> Result<()> {
+    let output_dir = root.join(apply_license::THIRD_PARTY_DIR);
+
+    for dir in dirs.iter().chain(&config.third_party) {
+        plan.append(apply_license::plan_third_party_licenses(
+            &root.join(dir),
+            &output_dir,
+        )?);
+    }
+
+    Ok(())
+}
+
+/// Executes the plan (unless this is a dry run or check) and reports the results.
+///
+/// The journal of the changes is saved in `project_dir`, so that `apply-license undo` can revert
+/// them.
+pub fn run_plan(plan: &Plan, args: &OutputArgs, project_dir: &Path) -> Result<ExitCode> {
+    let (report, planned) = if args.check || args.dry_run {
+        // Only show the changes that would actually be made.
+        let plan = plan.outstanding()?;
+
+        match args.format {
+            Format::Human => preview(&plan)?,
+            Format::Json => (),
+            Format::Github => {
+                let level = if args.check {
+                    github::Level::Error
+                } else {
+                    github::Level::Notice
+                };
+                annotate_plan(&plan, level)?;
+            }
+        }
+
+        (Report::from_plan(&plan), Some(plan))
+    } else {
+        let plan = &confirm_manifest_changes(plan, args)?;
+
+        let mut report = Report::new(plan);
+        let mut options = ExecuteOptions::new();
+        options.backup(args.backup).journal_dir(project_dir);
+        let journal = plan.execute_with(&options, |event| {
+            log_event(event);
+            report.record(event);
+        })?;
+
+        if args.commit {
+            let paths = journal
+                .entries
+                .iter()
+                .map(|entry| entry.path.clone())
+                .collect::<Vec<_>>();
+
+            if !paths.is_empty() {
+                let message = match &plan.license {
+                    Some(license) => format!("Apply {} license", license),
+                    None => String::from("Update license files"),
+                };
+                git::commit(project_dir, &paths, &message)?;
+                info!("committed changes: {}", message);
+            }
+        }
+
+        (report, None)
+    };
+
+    if report.dry_run {
+        for warning in &report.warnings {
+            match args.format {
+                Format::Github => github::annotate(github::Level::Warning, None, None, warning),
+                _ => warn!("{}", warning),
+            }
+        }
+
+        for problem in &report.problems {
+            match args.format {
+                Format::Github if args.check => {
+                    github::annotate(github::Level::Error, None, None, problem)
+                }
+                Format::Github => github::annotate(github::Level::Warning, None, None, problem),
+                _ => warn!("{}", problem),
+            }
+        }
+    }
+
+    match args.format {
+        Format::Human | Format::Github
+            if !report.dry_run && log::max_level() >= LevelFilter::Warn =>
+        {
+            print_summary(&report)
+        }
+        Format::Human | Format::Github => (),
+        Format::Json => match &planned {
+            // Include the plan itself, so that it can be reviewed and then executed exactly with
+            // `apply-license apply --plan`.
+            Some(plan) => {
+                let output = PlannedReport {
+                    report: &report,
+                    plan,
+                };
+                println!("{}", serde_json::to_string_pretty(&output)?)
+            }
+            None => println!("{}", serde_json::to_string_pretty(&report)?),
+        },
+    }
+
+    if args.checksums && args.format != Format::Json {
+        print_checksums(&report);
+    }
+
+    if args.check
+        && !(report.files.is_empty()
+            && report.manifest_changes.is_empty()
+            && report.problems.is_empty())
+    {
+        return Ok(ExitCode::from(EXIT_CHANGES_NEEDED));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The JSON output of a dry run or check: the report, along with the plan that it describes.
+#[derive(Serialize)]
+struct PlannedReport<'a> {
+    #[serde(flatten)]
+    report: &'a Report,
+
+    plan: &'a Plan,
+}
+
+/// Shows each manifest change in the plan and asks the user to confirm it, unless `--yes` is
+/// passed.
+///
+/// Returns a plan without the changes that were declined.
+fn confirm_manifest_changes(plan: &Plan, args: &OutputArgs) -> Result<Plan> {
+    let mut confirmed = plan.clone();
+    confirmed.actions.clear();
+
+    // Files that are kept because the manifest field pointing to them was kept.
+    let mut kept_files = vec![];
+
+    for action in &plan.actions {
+        let (path, change, skipped) = match action {
+            Action::RemoveFile { path } if kept_files.contains(path) => continue,
+            Action::UpdateManifest { path, field, value } => (
+                path,
+                format!("set {} = {:?}", field, value),
+                format!("setting {}", field),
+            ),
+            Action::RemoveManifestField { path, field } => match license_file(path, field) {
+                Some(license_file) => (
+                    path,
+                    format!("remove {} and delete {}", field, license_file.display()),
+                    format!("removing {}", field),
+                ),
+                None => (
+                    path,
+                    format!("remove {}", field),
+                    format!("removing {}", field),
+                ),
+            },
+            _ => {
+                confirmed.actions.push(action.clone());
+                continue;
+            }
+        };
+
+        // The preview goes to stderr, so that it doesn't mix with a report on stdout. It's only
+        // needed for the question, unless the output is for humans.
+        if !args.yes || (args.format == Format::Human && log::max_level() >= LevelFilter::Warn) {
+            eprintln!(
+                "{}: {}",
+                paint(Stream::Stderr, Style::Yellow, path.display()),
+                change
+            );
+        }
+
+        if !args.yes && !confirm(&format!("Update {}?", path.display()))? {
+            confirmed.warn(format!(
+                "skipped {} in {} (pass --yes to update it without asking)",
+                skipped,
+                path.display()
+            ));
+            if let Action::RemoveManifestField { path, field } = action {
+                kept_files.extend(license_file(path, field));
+            }
+            continue;
+        }
+
+        confirmed.actions.push(action.clone());
+    }
+
+    Ok(confirmed)
+}
+
+/// Returns the file that `package.license-file` points to in the manifest at `manifest_path`, if
+/// `field` is that field. The file is removed along with the field.
+fn license_file(manifest_path: &Path, field: &str) -> Option<PathBuf> {
+    if field != "package.license-file" {
+        return None;
+    }
+
+    let manifest: Document = fs::read_to_string(manifest_path).ok()?.parse().ok()?;
+    let license_file = manifest.get("package")?.get("license-file")?.as_str()?;
+    Some(
+        manifest_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(license_file),
+    )
+}
+
+/// Asks a yes-or-no question on the terminal, defaulting to no.
+///
+/// If stdin is not a terminal, the question is not asked and the answer is no.
+pub fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks a question on the terminal, returning the answer, or `None` if stdin is not a terminal or
+/// the answer is empty.
+fn prompt(question: &str) -> Result<Option<String>> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    eprint!("{} ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_owned()))
+}
+
+/// Prints the changes that the plan would make, with a diff for each file that already exists.
+fn preview(plan: &Plan) -> Result<()> {
+    for action in &plan.actions {
+        let path = action.path().display();
+
+        match action.preview()? {
+            (_, None) => println!("Would remove {}", paint(Stream::Stdout, Style::Red, &path)),
+            (None, Some(_)) => println!(
+                "Would create {}",
+                paint(Stream::Stdout, Style::Green, &path)
+            ),
+            (Some(old), Some(new)) => {
+                println!(
+                    "Would update {}",
+                    paint(Stream::Stdout, Style::Yellow, &path)
+                );
+                print_diff(&format!("a/{}", path), &old, &format!("b/{}", path), &new);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints an annotation for each change that the plan would make, pointing at the first line that
+/// would change.
+fn annotate_plan(plan: &Plan, level: github::Level) -> Result<()> {
+    for action in &plan.actions {
+        let path = action.path();
+
+        let (line, message) = match (action, action.preview()?) {
+            (_, (_, None)) => (None, format!("{} should be removed", path.display())),
+            (_, (None, Some(_))) => (None, format!("{} is missing", path.display())),
+            (Action::UpdateManifest { field, value, .. }, (Some(old), Some(new))) => (
+                Some(github::first_changed_line(&old, &new)),
+                format!("{} should be set to {:?}", field, value),
+            ),
+            (Action::RemoveManifestField { field, .. }, (Some(old), Some(new))) => (
+                Some(github::first_changed_line(&old, &new)),
+                format!("{} should be removed", field),
+            ),
+            (_, (Some(old), Some(new))) => (
+                Some(github::first_changed_line(&old, &new)),
+                format!("{} is out of date", path.display()),
+            ),
+        };
+
+        github::annotate(level, Some(path), line, &message);
+    }
+
+    Ok(())
+}
+
+/// Prints a concise summary of the changes that were made.
+fn print_summary(report: &Report) {
+    if let Some(license) = &report.license {
+        println!("Applied {}", paint(Stream::Stdout, Style::Green, license));
+    }
+
+    for (status, verb) in [
+        (FileStatus::Created, "Created"),
+        (FileStatus::Updated, "Updated"),
+        (FileStatus::Removed, "Removed"),
+    ] {
+        let files = report
+            .files
+            .iter()
+            .filter(|file| file.status == status)
+            .map(|file| file.path.display().to_string())
+            .collect::<Vec<_>>();
+
+        if !files.is_empty() {
+            println!("{}: {}", verb, files.join(", "));
+        }
+    }
+
+    if !report.backups.is_empty() {
+        let backups = report
+            .backups
+            .iter()
+            .map(|backup| backup.display().to_string())
+            .collect::<Vec<_>>();
+        println!("Backed up: {}", backups.join(", "));
+    }
+
+    for change in &report.manifest_changes {
+        match &change.value {
+            Some(value) => println!(
+                "Set {} = {:?} in {}",
+                change.field,
+                value,
+                change.path.display()
+            ),
+            None => println!("Removed {} from {}", change.field, change.path.display()),
+        }
+    }
+
+    if !report.warnings.is_empty() {
+        let count = report.warnings.len();
+        let plural = if count == 1 { "" } else { "s" };
+        println!(
+            "{}",
+            paint(
+                Stream::Stdout,
+                Style::Yellow,
+                format!("{} warning{}", count, plural)
+            )
+        );
+    }
+}
+
+/// Prints the checksum of each file that was written, or would be written in a dry run, in the
+/// format of `sha256sum`, so that it can be verified with `sha256sum --check`.
+fn print_checksums(report: &Report) {
+    for file in &report.files {
+        if let Some(sha256) = &file.sha256 {
+            println!("{}  {}", sha256, file.path.display());
+        }
+    }
+}
+
+fn log_event(event: Event) {
+    match event {
+        Event::FileWritten { path, .. } => info!("wrote {}", path.display()),
+        Event::FileRemoved(path) => info!("removed {}", path.display()),
+        Event::Unchanged(path) => debug!("{} is up to date", path.display()),
+        Event::ManifestUpdated { path, field, value } => {
+            info!("set {} = {:?} in {}", field, value, path.display())
+        }
+        Event::ManifestFieldRemoved { path, field } => {
+            info!("removed {} from {}", field, path.display())
+        }
+        Event::BackedUp { path, backup } => {
+            info!("backed up {} to {}", path.display(), backup.display())
+        }
+        Event::Warning(warning) => warn!("{}", warning),
+        _ => (),
+    }
+}
@@ -0,0 +1,159 @@
+//! Checking the license that GitHub detects for the repository, with `--remote-check`.
+
+use std::env;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use apply_license::{Expr, Plan};
+use log::info;
+use serde_json::Value;
+
+use super::git;
+
+/// The GitHub API, unless `GITHUB_API_URL` points to another one, such as GitHub Enterprise.
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Adds a warning to the plan if GitHub detects a different license for the repository
+/// containing `dir` than `license_expr`, such as when it doesn't recognize a modified license
+/// file.
+///
+/// Problems reaching GitHub are reported as warnings too, rather than failing the run.
+pub fn plan_remote_check(plan: &mut Plan, dir: &Path, license_expr: &str) -> Result<()> {
+    let dir = apply_license::existing_dir(dir);
+
+    let repository = match git::git(dir, ["remote", "get-url", "origin"])
+        .ok()
+        .and_then(|url| github_repository(url.trim()))
+    {
+        Some(repository) => repository,
+        None => {
+            plan.warn("skipping --remote-check, since the origin remote is not on GitHub");
+            return Ok(());
+        }
+    };
+
+    let detected = match detected_license(&repository) {
+        Ok(detected) => detected,
+        Err(e) => {
+            plan.warn(format!(
+                "could not check the license that GitHub detects for {}: {:#}",
+                repository, e
+            ));
+            return Ok(());
+        }
+    };
+
+    let detected = match detected.as_deref() {
+        None => {
+            plan.warn(format!(
+                "GitHub does not detect a license for {} (its license files may not be pushed \
+                 yet, or the repository may be private without a GITHUB_TOKEN)",
+                repository
+            ));
+            return Ok(());
+        }
+        Some("NOASSERTION") => {
+            plan.warn(format!(
+                "GitHub does not recognize the license of {}, so it won't be shown on the \
+                 repository page (is a license file modified?)",
+                repository
+            ));
+            return Ok(());
+        }
+        Some(detected) => detected,
+    };
+
+    let expr = Expr::parse(license_expr)?;
+    if expr
+        .licenses()
+        .iter()
+        .any(|license| base_id(license) == base_id(detected))
+    {
+        info!("GitHub detects {} for {}", detected, repository);
+    } else {
+        plan.warn(format!(
+            "GitHub detects the license of {} as {}, which is not in {}",
+            repository, detected, license_expr
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the `owner/name` of a GitHub repository from the URL of a git remote, such as
+/// `https://github.com/owner/name.git` or `git@github.com:owner/name.git`.
+fn github_repository(url: &str) -> Option<String> {
+    let (_, path) = url.split_once("github.com")?;
+    let path = path.strip_prefix([':', '/'])?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, name] if !owner.is_empty() && !name.is_empty() => Some(path.to_owned()),
+        _ => None,
+    }
+}
+
+/// Queries the GitHub API for the SPDX identifier of the license that it detects for
+/// `repository`, or `None` if it doesn't detect one.
+///
+/// The request is made with `curl`, authenticated with `GITHUB_TOKEN` if it's set.
+fn detected_license(repository: &str) -> Result<Option<String>> {
+    let api_url = env::var("GITHUB_API_URL").unwrap_or_else(|_| String::from(GITHUB_API_URL));
+
+    let token = env::var("GITHUB_TOKEN").ok();
+
+    let mut curl = Command::new("curl");
+    curl.args(["--silent", "--show-error", "--location"])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .args(["--write-out", "\n%{http_code}"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // The token is passed on stdin, since arguments are visible to other users in `ps`.
+    if token.is_some() {
+        curl.args(["--header", "@-"]);
+    }
+    let mut child = curl
+        .arg(format!(
+            "{}/repos/{}/license",
+            api_url.trim_end_matches('/'),
+            repository
+        ))
+        .spawn()
+        .context("could not run curl")?;
+
+    let mut stdin = child.stdin.take().unwrap();
+    if let Some(token) = token {
+        writeln!(stdin, "Authorization: Bearer {}", token)
+            .context("could not pass GITHUB_TOKEN to curl")?;
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().context("could not run curl")?;
+
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let output = String::from_utf8(output.stdout)?;
+    let (body, status) = output.rsplit_once('\n').unwrap_or(("", &output));
+    match status {
+        "200" => {}
+        "404" => return Ok(None),
+        status => bail!("GitHub responded with HTTP {}", status),
+    }
+
+    let body: Value = serde_json::from_str(body).context("could not parse GitHub's response")?;
+    Ok(body["license"]["spdx_id"].as_str().map(str::to_owned))
+}
+
+/// Returns the identifier of a license without a `-only` or `-or-later` suffix, which GitHub
+/// leaves off.
+fn base_id(id: &str) -> &str {
+    apply_license::supported_license(id)
+        .map(|license| license.spdx.as_str())
+        .unwrap_or(id)
+}
@@ -0,0 +1,251 @@
+//! Project configuration, read from `.apply-license.toml`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{CopyrightSymbol, FileNameStyle, HolderStyle};
+
+/// The name of the configuration file, relative to the project.
+pub const CONFIG_FILE: &str = ".apply-license.toml";
+
+/// The directory that the documentation license is applied to, unless the configuration says
+/// otherwise.
+pub const DEFAULT_DOCS_DIR: &str = "docs";
+
+/// The path of the user configuration file, relative to the user's configuration directory.
+pub const USER_CONFIG_FILE: &str = "apply-license/config.toml";
+
+/// Project configuration.
+///
+/// # Example
+///
+/// ```toml
+/// copyright = "Copyright {{year}} {{company}}. All rights reserved."
+///
+/// [vars]
+/// company = "Example Inc."
+///
+/// # Use different licenses for the documentation and bundled fonts.
+/// [paths]
+/// "docs/" = "CC-BY-4.0"
+/// "assets/fonts/" = "OFL-1.1"
+///
+/// # Keep the headers of generated code short.
+/// [headers]
+/// "*.pb.rs" = "SPDX-License-Identifier: {{license}}"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Config {
+    /// License expressions for subdirectories of the project that are licensed differently from
+    /// the rest of it, keyed by the path of the subdirectory.
+    #[serde(default)]
+    pub paths: BTreeMap<PathBuf, String>,
+
+    /// A license expression for the documentation, such as `CC-BY-4.0`, when it's licensed
+    /// differently from the code.
+    pub docs_license: Option<String>,
+
+    /// The directory of the documentation that [`Config::docs_license`] applies to, relative to the
+    /// project. Defaults to [`DEFAULT_DOCS_DIR`].
+    pub docs_dir: Option<PathBuf>,
+
+    /// A handlebars template of the copyright line, such as
+    /// `Copyright {{year}} {{copyright_holders}}. All rights reserved.`
+    pub copyright: Option<String>,
+
+    /// How the copyright symbol is written in copyright lines.
+    pub copyright_symbol: Option<CopyrightSymbol>,
+
+    /// Who is named in copyright lines, such as `contributors` for "The {{project}} Contributors".
+    pub holder_style: Option<HolderStyle>,
+
+    /// Whether to sort the authors in copyright lines alphabetically, as with `--sort-authors`.
+    #[serde(default)]
+    pub sort_authors: bool,
+
+    /// Whether to strip whitespace from the end of each line of the generated files.
+    #[serde(default)]
+    pub trim_trailing_whitespace: bool,
+
+    /// Whether to ensure that the generated files end with exactly one newline.
+    #[serde(default)]
+    pub insert_final_newline: bool,
+
+    /// Whether to leave the year out of copyright lines, as with `--no-year`.
+    #[serde(default)]
+    pub no_year: bool,
+
+    /// Whether to generate the same files on every platform, as with `--deterministic`.
+    #[serde(default)]
+    pub deterministic: bool,
+
+    /// Whether to end each license file with a line saying that it was generated by apply-license,
+    /// as with `--provenance`.
+    #[serde(default)]
+    pub provenance: bool,
+
+    /// The convention that license files are named by, such as `gnu` for `COPYING` and
+    /// `COPYING.LESSER`.
+    pub file_name_style: Option<FileNameStyle>,
+
+    /// File names for licenses, keyed by SPDX identifier, such as `LICENSE.Apache2` for
+    /// `Apache-2.0`, instead of `LICENSE` or `LICENSE-{id}`.
+    #[serde(default)]
+    pub file_names: BTreeMap<String, PathBuf>,
+
+    /// Extra variables for the template context, such as a company name for a custom copyright
+    /// line.
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+
+    /// Handlebars templates of source file headers, keyed by a glob of the files that they're for,
+    /// such as a shorter header for `*.pb.rs`. Patterns are tried in sorted order. See
+    /// [`Renderer::header_template`](crate::Renderer::header_template).
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Directories of vendored third-party code, such as `vendor/`, whose license files are
+    /// collected into [`THIRD_PARTY_DIR`](crate::THIRD_PARTY_DIR).
+    #[serde(default)]
+    pub third_party: Vec<PathBuf>,
+}
+
+impl Config {
+    /// Returns the directory of the documentation, relative to the project.
+    pub fn docs_dir(&self) -> &Path {
+        self.docs_dir
+            .as_deref()
+            .unwrap_or_else(|| Path::new(DEFAULT_DOCS_DIR))
+    }
+
+    /// Loads the configuration file in the project directory `dir`, or returns the default
+    /// configuration if there isn't one.
+    pub fn load(dir: &Path) -> Result<Config> {
+        let path = dir.join(CONFIG_FILE);
+
+        let config = match fs::read_to_string(&path) {
+            Ok(config) => config,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(e).with_context(|| format!("could not read {}", path.display())),
+        };
+
+        toml_edit::easy::from_str(&config)
+            .with_context(|| format!("could not parse {}", path.display()))
+    }
+}
+
+/// Settings that apply to every project of a user, such as who they are.
+///
+/// # Example
+///
+/// ```toml
+/// authors = ["John Doe"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct UserConfig {
+    /// The authors to name in copyright lines when none are given for a project.
+    #[serde(default)]
+    pub authors: Vec<String>,
+}
+
+impl UserConfig {
+    /// Returns the path of the user configuration file, in `$XDG_CONFIG_HOME`, `~/.config`, or
+    /// `%APPDATA%`, or `None` if there is no configuration directory.
+    pub fn path() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+        Some(config_dir.join(USER_CONFIG_FILE))
+    }
+
+    /// Loads the user configuration file at `path`, or returns the default configuration if there
+    /// isn't one.
+    pub fn load(path: &Path) -> Result<UserConfig> {
+        let config = match fs::read_to_string(path) {
+            Ok(config) => config,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(UserConfig::default()),
+            Err(e) => return Err(e).with_context(|| format!("could not read {}", path.display())),
+        };
+
+        toml_edit::easy::from_str(&config)
+            .with_context(|| format!("could not parse {}", path.display()))
+    }
+
+    /// Writes the user configuration to `path`, creating its directory if necessary.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("could not create directory {}", parent.display()))?;
+        }
+
+        fs::write(path, toml_edit::easy::to_string(self)?)
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::{Config, UserConfig, CONFIG_FILE};
+
+    #[test]
+    fn load() -> Result<()> {
+        let dir = tempdir()?;
+        assert_eq!(Config::load(dir.path())?, Config::default());
+
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[paths]\n\"docs/\" = \"CC-BY-4.0\"\n",
+        )?;
+        let config = Config::load(dir.path())?;
+        assert_eq!(config.paths[Path::new("docs")], "CC-BY-4.0");
+
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[headers]\n\"*.pb.rs\" = \"SPDX-License-Identifier: {{license}}\"\n",
+        )?;
+        let config = Config::load(dir.path())?;
+        assert_eq!(
+            config.headers["*.pb.rs"],
+            "SPDX-License-Identifier: {{license}}"
+        );
+
+        fs::write(dir.path().join(CONFIG_FILE), "unknown = true\n")?;
+        assert!(Config::load(dir.path()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn user_config() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("apply-license/config.toml");
+        assert_eq!(UserConfig::load(&path)?, UserConfig::default());
+
+        let config = UserConfig {
+            authors: vec![String::from("John Doe")],
+        };
+        config.save(&path)?;
+        assert_eq!(UserConfig::load(&path)?, config);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Identifying the license of an existing license file.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{supported_licenses, License};
+
+/// A license that an existing text was identified as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct Detection {
+    /// The closest matching license.
+    pub license: &'static License,
+
+    /// How closely the text matches the license, from 0 (nothing in common) to 1 (identical,
+    /// ignoring case, punctuation, whitespace, and template variables such as the copyright
+    /// holders).
+    pub confidence: f64,
+}
+
+/// Finds the supported license whose text most closely matches `text`, if any of them have
+/// anything in common with it.
+pub fn detect_license(text: &str) -> Option<Detection> {
+    let bigrams = bigrams(text);
+
+    supported_licenses()
+        .map(|license| Detection {
+            license,
+            confidence: similarity(&bigrams, &bigrams_for_template(license)),
+        })
+        .filter(|detection| detection.confidence > 0.0)
+        .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+}
+
+/// Counts each pair of adjacent words in the text, ignoring case and punctuation.
+fn bigrams(text: &str) -> HashMap<(String, String), usize> {
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+
+    let mut bigrams = HashMap::new();
+    for pair in words.windows(2) {
+        *bigrams
+            .entry((pair[0].clone(), pair[1].clone()))
+            .or_default() += 1;
+    }
+    bigrams
+}
+
+fn bigrams_for_template(license: &License) -> HashMap<(String, String), usize> {
+    static VAR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{[^}]*\}\}").unwrap());
+
+    let template = license.text.replace("{{copyright}}", &license.copyright);
+    bigrams(&VAR_RE.replace_all(&template, " "))
+}
+
+/// The Sørensen–Dice coefficient of two multisets of bigrams.
+fn similarity(a: &HashMap<(String, String), usize>, b: &HashMap<(String, String), usize>) -> f64 {
+    let total = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let shared = a
+        .iter()
+        .map(|(bigram, count)| b.get(bigram).map_or(0, |other| *count.min(other)))
+        .sum::<usize>();
+
+    2.0 * shared as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::detect_license;
+    use crate::{parse_spdx, render_license_text, supported_licenses};
+
+    #[test]
+    fn detect_rendered_license() {
+        for license in supported_licenses() {
+            let id = license.spdx.as_str();
+            let licenses = parse_spdx(id).unwrap();
+            let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+
+            // Some licenses are rendered along with others that they build on, like the LGPL.
+            let text = files
+                .get(Path::new(&format!("LICENSE-{}", license.identifier)))
+                .unwrap_or_else(|| &files[Path::new("LICENSE")]);
+
+            let detection = detect_license(text).unwrap();
+            assert_eq!(detection.license.spdx, id);
+            assert!(detection.confidence > 0.95, "{}", detection.confidence);
+        }
+    }
+
+    #[test]
+    fn detect_reformatted_license() {
+        // MIT-0 differs from MIT only in dropping the conditions, so they're needed to tell them
+        // apart.
+        let text = "copyright 2020 jane doe. PERMISSION is hereby granted, free of charge, to \
+                    any person obtaining a copy of this software, subject to the following \
+                    conditions";
+        let detection = detect_license(text).unwrap();
+        assert_eq!(detection.license.spdx, "MIT");
+        assert!(detection.confidence < 0.5);
+    }
+
+    #[test]
+    fn detect_nothing() {
+        assert!(detect_license("").is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! Parsing of SPDX license expressions.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Result;
+use spdx::expression::{ExprNode, ExpressionReq, Operator};
+use spdx::{Expression, LicenseItem, ParseMode};
+
+/// A parsed SPDX license expression, such as `MIT OR Apache-2.0`.
+///
+/// The cargo manifest format allows combining licenses with `/`, which is parsed as `OR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// A single license identifier, such as `MIT` or `GPL-2.0+`.
+    License(String),
+
+    /// A license identifier with an exception, such as `GPL-2.0 WITH Classpath-exception-2.0`.
+    With {
+        /// The license identifier.
+        license: String,
+
+        /// The exception identifier.
+        exception: String,
+    },
+
+    /// Both expressions apply.
+    And(Box<Expr>, Box<Expr>),
+
+    /// Either expression may apply.
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parses an SPDX license expression.
+    ///
+    /// Each identifier must be in the SPDX license list, or be a `LicenseRef-` reference. Like
+    /// crates.io, this accepts some common mistakes, such as lowercase operators and imprecise
+    /// license names.
+    pub fn parse(expr: &str) -> Result<Expr> {
+        let parsed = Expression::parse_mode(expr, ParseMode::LAX)
+            .map_err(|e| anyhow::Error::new(e).context("invalid license expression"))?;
+
+        let mut stack = vec![];
+        for node in parsed.iter() {
+            match node {
+                ExprNode::Req(req) => stack.push(requirement(expr, req)),
+                ExprNode::Op(op) => {
+                    let rhs = Box::new(stack.pop().unwrap());
+                    let lhs = Box::new(stack.pop().unwrap());
+                    stack.push(match op {
+                        Operator::And => Expr::And(lhs, rhs),
+                        Operator::Or => Expr::Or(lhs, rhs),
+                    });
+                }
+            }
+        }
+
+        Ok(stack.pop().unwrap())
+    }
+
+    /// Returns the license identifiers in this expression, in the order that they appear.
+    pub fn licenses(&self) -> Vec<&str> {
+        let mut licenses = vec![];
+
+        self.walk(&mut |expr| match expr {
+            Expr::License(license) | Expr::With { license, .. } => licenses.push(license.as_str()),
+            _ => (),
+        });
+
+        licenses
+    }
+
+    /// Returns the exception identifiers in this expression, in the order that they appear.
+    pub fn exceptions(&self) -> Vec<&str> {
+        let mut exceptions = vec![];
+
+        self.walk(&mut |expr| {
+            if let Expr::With { exception, .. } = expr {
+                exceptions.push(exception.as_str());
+            }
+        });
+
+        exceptions
+    }
+
+    /// Calls `f` on this expression and each of its subexpressions, in pre-order.
+    pub fn walk<'a, F>(&'a self, f: &mut F)
+    where
+        F: FnMut(&'a Expr),
+    {
+        f(self);
+
+        if let Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) = self {
+            lhs.walk(f);
+            rhs.walk(f);
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(..) => 0,
+            Expr::And(..) => 1,
+            Expr::License(_) | Expr::With { .. } => 2,
+        }
+    }
+
+    fn fmt_binary(&self, lhs: &Expr, op: &str, rhs: &Expr, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, operand) in [lhs, rhs].into_iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", op)?;
+            }
+
+            if operand.precedence() < self.precedence() {
+                write!(f, "({})", operand)?;
+            } else {
+                write!(f, "{}", operand)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Expr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Expr::parse(s)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::License(license) => write!(f, "{}", license),
+            Expr::With { license, exception } => write!(f, "{} WITH {}", license, exception),
+            Expr::And(lhs, rhs) => self.fmt_binary(lhs, "AND", rhs, f),
+            Expr::Or(lhs, rhs) => self.fmt_binary(lhs, "OR", rhs, f),
+        }
+    }
+}
+
+/// Converts a license requirement parsed by the `spdx` crate, using the identifier as it was
+/// written in `expr` where possible.
+fn requirement(expr: &str, req: &ExpressionReq) -> Expr {
+    let license = match &req.req.license {
+        LicenseItem::Spdx { id, or_later } => {
+            // The parser normalizes GNU identifiers such as `GPL-3.0-only` to `GPL-3.0`, so look
+            // the identifier up again to preserve the original.
+            let written = &expr[req.span.start as usize..req.span.end as usize];
+            let name = spdx::license_id(written).map_or(id.name, |id| id.name);
+
+            if *or_later && !name.ends_with("-or-later") {
+                format!("{}+", name)
+            } else {
+                name.to_owned()
+            }
+        }
+        LicenseItem::Other {
+            doc_ref: Some(doc_ref),
+            lic_ref,
+        } => format!("DocumentRef-{}:LicenseRef-{}", doc_ref, lic_ref),
+        LicenseItem::Other {
+            doc_ref: None,
+            lic_ref,
+        } => format!("LicenseRef-{}", lic_ref),
+    };
+
+    match req.req.exception {
+        Some(exception) => Expr::With {
+            license,
+            exception: exception.name.to_owned(),
+        },
+        None => Expr::License(license),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expr;
+
+    fn license(id: &str) -> Box<Expr> {
+        Box::new(Expr::License(id.to_owned()))
+    }
+
+    #[test]
+    fn single() {
+        assert_eq!(Expr::parse("MIT").unwrap(), *license("MIT"));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            Expr::parse("MIT OR Apache-2.0 AND Zlib").unwrap(),
+            Expr::Or(
+                license("MIT"),
+                Box::new(Expr::And(license("Apache-2.0"), license("Zlib")))
+            )
+        );
+    }
+
+    #[test]
+    fn with_exception() {
+        let expr = Expr::parse("GPL-2.0+ WITH Classpath-exception-2.0 OR MIT").unwrap();
+        assert_eq!(expr.licenses(), &["GPL-2.0+", "MIT"]);
+        assert_eq!(expr.exceptions(), &["Classpath-exception-2.0"]);
+    }
+
+    #[test]
+    fn cargo_slash() {
+        assert_eq!(
+            Expr::parse("MIT/Apache-2.0").unwrap(),
+            Expr::parse("MIT OR Apache-2.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn preserves_identifiers() {
+        for expr in [
+            "GPL-3.0-only",
+            "GPL-3.0-or-later",
+            "GPL-2.0+",
+            "Apache-2.0 WITH LLVM-exception",
+            "LicenseRef-Custom",
+        ] {
+            assert_eq!(Expr::parse(expr).unwrap().to_string(), expr);
+        }
+    }
+
+    #[test]
+    fn display() {
+        for expr in [
+            "MIT",
+            "MIT OR Apache-2.0",
+            "(MIT OR Apache-2.0) AND Zlib",
+            "GPL-3.0 WITH GCC-exception-3.1 OR MIT AND Zlib",
+        ] {
+            assert_eq!(Expr::parse(expr).unwrap().to_string(), expr);
+        }
+
+        assert_eq!(
+            Expr::parse("MIT/Apache-2.0").unwrap().to_string(),
+            "MIT OR Apache-2.0"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for expr in [
+            "",
+            "MIT OR",
+            "(MIT",
+            "MIT)",
+            "MIT WITH",
+            "MIT Apache-2.0",
+            "M!T",
+            "foobar",
+            "MIT WITH foobar",
+        ] {
+            assert!(Expr::parse(expr).is_err(), "{:?} should not parse", expr);
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! License headers for source files, such as `// SPDX-License-Identifier: MIT`.
+
+use std::borrow::Borrow;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+
+use crate::{Plan, Renderer};
+
+/// The name of the file listing paths that headers shouldn't be added to, in gitignore syntax.
+pub const IGNORE_FILE: &str = ".licenseignore";
+
+/// The number of lines at the start of a file that are searched for an existing header.
+const HEADER_LINES: usize = 10;
+
+/// How comments are written in a kind of source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CommentStyle {
+    /// The line that opens a block comment around the header, if any.
+    pub start: Option<&'static str>,
+
+    /// The prefix of each line of the header.
+    pub prefix: &'static str,
+
+    /// The line that closes a block comment around the header, if any.
+    pub end: Option<&'static str>,
+}
+
+impl CommentStyle {
+    const fn line(prefix: &'static str) -> CommentStyle {
+        CommentStyle {
+            start: None,
+            prefix,
+            end: None,
+        }
+    }
+
+    const fn block(start: &'static str, prefix: &'static str, end: &'static str) -> CommentStyle {
+        CommentStyle {
+            start: Some(start),
+            prefix,
+            end: Some(end),
+        }
+    }
+
+    /// Formats lines of text as a comment, ending with a newline.
+    pub fn comment<S: AsRef<str>>(&self, lines: &[S]) -> String {
+        let mut comment = String::new();
+
+        if let Some(start) = self.start {
+            comment.push_str(start);
+            comment.push('\n');
+        }
+
+        for line in lines {
+            let line = format!("{} {}", self.prefix, line.as_ref());
+            comment.push_str(line.trim_end());
+            comment.push('\n');
+        }
+
+        if let Some(end) = self.end {
+            comment.push_str(end);
+            comment.push('\n');
+        }
+
+        comment
+    }
+}
+
+const SLASHES: CommentStyle = CommentStyle::line("//");
+const HASH: CommentStyle = CommentStyle::line("#");
+const DASHES: CommentStyle = CommentStyle::line("--");
+const SEMICOLONS: CommentStyle = CommentStyle::line(";;");
+const PERCENT: CommentStyle = CommentStyle::line("%");
+const C_BLOCK: CommentStyle = CommentStyle::block("/*", " *", " */");
+const XML_BLOCK: CommentStyle = CommentStyle::block("<!--", " ", "-->");
+
+/// Comment styles by file extension.
+const EXTENSIONS: &[(&str, CommentStyle)] = &[
+    ("rs", SLASHES),
+    ("c", SLASHES),
+    ("h", SLASHES),
+    ("cc", SLASHES),
+    ("cpp", SLASHES),
+    ("cxx", SLASHES),
+    ("hh", SLASHES),
+    ("hpp", SLASHES),
+    ("cs", SLASHES),
+    ("go", SLASHES),
+    ("java", SLASHES),
+    ("kt", SLASHES),
+    ("kts", SLASHES),
+    ("scala", SLASHES),
+    ("swift", SLASHES),
+    ("dart", SLASHES),
+    ("zig", SLASHES),
+    ("proto", SLASHES),
+    ("js", SLASHES),
+    ("jsx", SLASHES),
+    ("mjs", SLASHES),
+    ("cjs", SLASHES),
+    ("ts", SLASHES),
+    ("tsx", SLASHES),
+    ("scss", SLASHES),
+    ("css", C_BLOCK),
+    ("py", HASH),
+    ("rb", HASH),
+    ("pl", HASH),
+    ("pm", HASH),
+    ("sh", HASH),
+    ("bash", HASH),
+    ("ksh", HASH),
+    ("zsh", HASH),
+    ("fish", HASH),
+    ("ps1", HASH),
+    ("r", HASH),
+    ("nix", HASH),
+    ("cmake", HASH),
+    ("mk", HASH),
+    ("dockerfile", HASH),
+    ("toml", HASH),
+    ("yaml", HASH),
+    ("yml", HASH),
+    ("sql", DASHES),
+    ("lua", DASHES),
+    ("hs", DASHES),
+    ("elm", DASHES),
+    ("lisp", SEMICOLONS),
+    ("el", SEMICOLONS),
+    ("clj", SEMICOLONS),
+    ("scm", SEMICOLONS),
+    ("erl", PERCENT),
+    ("tex", PERCENT),
+    ("html", XML_BLOCK),
+    ("htm", XML_BLOCK),
+    ("xhtml", XML_BLOCK),
+    ("vue", XML_BLOCK),
+    ("xml", XML_BLOCK),
+    ("xsd", XML_BLOCK),
+    ("xsl", XML_BLOCK),
+    ("xslt", XML_BLOCK),
+    ("svg", XML_BLOCK),
+];
+
+/// Comment styles for files that are recognized by name rather than extension.
+const FILE_NAMES: &[(&str, CommentStyle)] = &[
+    ("Makefile", HASH),
+    ("makefile", HASH),
+    ("GNUmakefile", HASH),
+    ("Dockerfile", HASH),
+    ("Containerfile", HASH),
+    ("CMakeLists.txt", HASH),
+];
+
+/// Returns the comment style for a source file, or `None` if headers can't be added to it.
+pub fn comment_style(path: &Path) -> Option<CommentStyle> {
+    let file_name = path.file_name()?.to_str()?;
+    if let Some((_, style)) = FILE_NAMES.iter().find(|(name, _)| *name == file_name) {
+        return Some(*style);
+    }
+
+    let extension = path.extension()?.to_str()?;
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+        .map(|(_, style)| *style)
+}
+
+/// Returns true if the start of the file already has a license header or copyright notice.
+pub fn has_header(contents: &str) -> bool {
+    contents.lines().take(HEADER_LINES).any(|line| {
+        line.contains("SPDX-License-Identifier:") || line.to_lowercase().contains("copyright")
+    })
+}
+
+/// Returns the license expression that the header at the start of the file declares with an
+/// `SPDX-License-Identifier` tag, if it has one.
+pub fn header_license(contents: &str) -> Option<&str> {
+    contents.lines().take(HEADER_LINES).find_map(|line| {
+        let (_, expr) = line.split_once("SPDX-License-Identifier:")?;
+        let expr = expr.trim();
+        let expr = expr
+            .strip_suffix("*/")
+            .or_else(|| expr.strip_suffix("-->"))
+            .unwrap_or(expr)
+            .trim_end();
+        Some(expr).filter(|expr| !expr.is_empty())
+    })
+}
+
+/// Inserts a header at the start of the file, after any shebang line or XML declaration, which
+/// must come first.
+pub fn insert_header(contents: &str, header: &str) -> String {
+    let (preamble, rest) = if contents.starts_with("#!") || contents.starts_with("<?xml") {
+        match contents.find('\n') {
+            Some(end) => contents.split_at(end + 1),
+            None => (contents, ""),
+        }
+    } else {
+        ("", contents)
+    };
+
+    let mut result = String::from(preamble);
+    if !preamble.is_empty() && !preamble.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(header);
+    result.push('\n');
+    result.push_str(rest);
+    result
+}
+
+/// Options controlling which files are searched for source files.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct WalkOptions {
+    /// Whether to include files in git submodules (and other nested repositories), which usually
+    /// have their own licensing.
+    pub include_submodules: bool,
+}
+
+impl WalkOptions {
+    /// Creates the default options.
+    pub fn new() -> WalkOptions {
+        WalkOptions::default()
+    }
+
+    /// Sets whether to include files in git submodules and other nested repositories.
+    pub fn include_submodules(&mut self, include: bool) -> &mut WalkOptions {
+        self.include_submodules = include;
+        self
+    }
+}
+
+/// Returns the contents of the file at `path` with a header declaring `license_expr`, or `None`
+/// if the file already has a header, is empty, or isn't a source file that headers can be added
+/// to.
+pub fn with_header<S: Borrow<str>>(
+    path: &Path,
+    renderer: &Renderer,
+    license_expr: &str,
+    authors: &[S],
+) -> Result<Option<String>> {
+    header_contents(path, path, renderer, license_expr, authors)
+}
+
+/// Like [`with_header`], choosing the header template by `template_path` instead of `path`, such
+/// as the path relative to the project.
+fn header_contents<S: Borrow<str>>(
+    path: &Path,
+    template_path: &Path,
+    renderer: &Renderer,
+    license_expr: &str,
+    authors: &[S],
+) -> Result<Option<String>> {
+    let style = match comment_style(path) {
+        Some(style) => style,
+        None => return Ok(None),
+    };
+
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("could not read {}", path.display())),
+    };
+
+    if contents.trim().is_empty() || has_header(&contents) {
+        return Ok(None);
+    }
+
+    let header = renderer.header_for(template_path, license_expr, authors, &style)?;
+    Ok(Some(insert_header(&contents, &header)))
+}
+
+/// Returns the source files under `dir`, skipping hidden files, files ignored by git or by
+/// [`IGNORE_FILE`], and (unless the options say otherwise) git submodules.
+pub fn source_files(dir: &Path, options: &WalkOptions) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    let include_submodules = options.include_submodules;
+    let walk = WalkBuilder::new(dir)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(move |entry| {
+            include_submodules || entry.depth() == 0 || !entry.path().join(".git").exists()
+        })
+        .build();
+
+    for entry in walk {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|ty| ty.is_file()) && comment_style(entry.path()).is_some()
+        {
+            files.push(entry.into_path());
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Returns a plan that adds a header declaring `license_expr` to each source file under `dir`
+/// that doesn't already have one. Header templates are matched against paths relative to `dir`.
+pub fn plan_headers<S: Borrow<str>>(
+    dir: &Path,
+    options: &WalkOptions,
+    renderer: &Renderer,
+    license_expr: &str,
+    authors: &[S],
+) -> Result<Plan> {
+    let mut plan = Plan::new();
+
+    for path in source_files(dir, options)? {
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        if let Some(contents) = header_contents(&path, relative, renderer, license_expr, authors)? {
+            plan.write_file(path, contents);
+        }
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use super::{
+        comment_style, has_header, header_license, insert_header, plan_headers, WalkOptions,
+        C_BLOCK, HASH, IGNORE_FILE, XML_BLOCK,
+    };
+    use crate::Renderer;
+
+    #[test]
+    fn comment_styles() {
+        assert_eq!(comment_style(Path::new("src/main.py")), Some(HASH));
+        assert_eq!(comment_style(Path::new("Makefile")), Some(HASH));
+        assert_eq!(comment_style(Path::new("docker/Containerfile")), Some(HASH));
+        assert_eq!(comment_style(Path::new("index.html")), Some(XML_BLOCK));
+        assert_eq!(comment_style(Path::new("README")), None);
+
+        assert_eq!(
+            C_BLOCK.comment(&["foo", "", "bar"]),
+            "/*\n * foo\n *\n * bar\n */\n"
+        );
+        assert_eq!(
+            XML_BLOCK.comment(&["foo", "", "bar"]),
+            "<!--\n  foo\n\n  bar\n-->\n"
+        );
+    }
+
+    #[test]
+    fn insert() {
+        assert_eq!(
+            insert_header("fn main() {}\n", "// MIT\n"),
+            "// MIT\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            insert_header("#!/bin/sh\necho\n", "# MIT\n"),
+            "#!/bin/sh\n# MIT\n\necho\n"
+        );
+        assert_eq!(
+            insert_header("<?xml version=\"1.0\"?>\n<a/>\n", "<!-- MIT -->\n"),
+            "<?xml version=\"1.0\"?>\n<!-- MIT -->\n\n<a/>\n"
+        );
+        assert!(has_header("// Copyright (c) 2020 John Doe\n"));
+        assert!(!has_header("fn main() {}\n"));
+    }
+
+    #[test]
+    fn license() {
+        assert_eq!(
+            header_license("// SPDX-License-Identifier: MIT OR Apache-2.0\n"),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            header_license("#!/bin/sh\n# SPDX-License-Identifier: MIT\n"),
+            Some("MIT")
+        );
+        assert_eq!(
+            header_license("/* SPDX-License-Identifier: GPL-3.0-or-later */\n"),
+            Some("GPL-3.0-or-later")
+        );
+        assert_eq!(header_license("// Copyright (c) 2020 John Doe\n"), None);
+    }
+
+    #[test]
+    fn plan() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+        fs::write(dir.join("main.rs"), "fn main() {}\n")?;
+        fs::write(dir.join("lib.rs"), "// SPDX-License-Identifier: MIT\n")?;
+        fs::write(dir.join("notes.txt"), "notes\n")?;
+
+        let plan = plan_headers(
+            dir,
+            &WalkOptions::new(),
+            &Renderer::new(),
+            "MIT",
+            &["John Doe"],
+        )?;
+        assert_eq!(plan.actions.len(), 1);
+        let (_, contents) = plan.actions[0].preview()?;
+        let contents = contents.unwrap();
+        assert!(contents.starts_with("// Copyright (c) "));
+        assert!(contents.contains("John Doe\n// SPDX-License-Identifier: MIT\n\nfn main"));
+
+        fs::create_dir_all(dir.join("vendor/foo"))?;
+        fs::write(
+            dir.join("vendor/foo/.git"),
+            "gitdir: ../../.git/modules/foo\n",
+        )?;
+        fs::write(dir.join("vendor/foo/lib.rs"), "fn foo() {}\n")?;
+
+        let plan = plan_headers(
+            dir,
+            &WalkOptions::new(),
+            &Renderer::new(),
+            "MIT",
+            &["John Doe"],
+        )?;
+        assert_eq!(plan.actions.len(), 1);
+
+        let plan = plan_headers(
+            dir,
+            WalkOptions::new().include_submodules(true),
+            &Renderer::new(),
+            "MIT",
+            &["John Doe"],
+        )?;
+        assert_eq!(plan.actions.len(), 2);
+
+        fs::write(dir.join(IGNORE_FILE), "main.rs\n")?;
+        let plan = plan_headers(
+            dir,
+            &WalkOptions::new(),
+            &Renderer::new(),
+            "MIT",
+            &["John Doe"],
+        )?;
+        assert!(plan.actions.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn header_templates() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+        fs::create_dir_all(dir.join("src/generated"))?;
+        fs::write(dir.join("src/main.rs"), "fn main() {}\n")?;
+        fs::write(dir.join("src/foo.pb.rs"), "struct Foo;\n")?;
+        fs::write(dir.join("src/generated/bar.rs"), "struct Bar;\n")?;
+
+        let mut renderer = Renderer::new();
+        renderer
+            .header_template("*.pb.rs", "SPDX-License-Identifier: {{license}}")
+            .header_template(
+                "src/generated/**",
+                "{{copyright}}\nGenerated code.\nSPDX-License-Identifier: {{license}}",
+            );
+        let plan = plan_headers(dir, &WalkOptions::new(), &renderer, "MIT", &["John Doe"])?;
+        let contents = plan
+            .actions
+            .iter()
+            .map(|action| Ok(action.preview()?.1.unwrap()))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            contents[0],
+            "// SPDX-License-Identifier: MIT\n\nstruct Foo;\n"
+        );
+        assert!(
+            contents[1].contains("John Doe\n// Generated code.\n// SPDX-License-Identifier: MIT\n")
+        );
+        assert!(contents[2].starts_with("// Copyright (c) "));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! Recording changes so that they can be reverted.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::plan::write_atomic;
+
+/// The directory, relative to the project, where the journal is stored.
+pub const JOURNAL_DIR: &str = ".apply-license";
+
+const JOURNAL_FILE: &str = "journal.json";
+
+/// A record of the files changed by executing a [`Plan`](crate::Plan), which can be used to revert
+/// them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journal {
+    /// The changes that were made, in order.
+    pub entries: Vec<JournalEntry>,
+}
+
+/// A change to a single file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// The absolute path of the file.
+    pub path: PathBuf,
+
+    /// The contents of the file before it was changed, or `None` if it was created.
+    pub original: Option<String>,
+
+    /// The contents of the file after it was changed, or `None` if it was removed.
+    pub written: Option<String>,
+}
+
+/// The result of reverting a single [`JournalEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reverted<'a> {
+    /// The file was restored to its original contents, or recreated if it was removed.
+    Restored(&'a Path),
+
+    /// The file was created, so it was removed.
+    Removed(&'a Path),
+
+    /// The file has been changed since it was written, so it was left alone.
+    Modified(&'a Path),
+}
+
+impl Journal {
+    /// Creates an empty journal.
+    pub fn new() -> Journal {
+        Journal::default()
+    }
+
+    /// Returns whether no changes were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Records that the file at `path` was changed from `original` to `written`.
+    pub fn record(
+        &mut self,
+        path: &Path,
+        original: Option<String>,
+        written: Option<String>,
+    ) -> Result<()> {
+        let path = if path.is_absolute() {
+            path.to_owned()
+        } else {
+            env::current_dir()?.join(path)
+        };
+
+        self.entries.push(JournalEntry {
+            path,
+            original,
+            written,
+        });
+
+        Ok(())
+    }
+
+    /// Loads the journal saved in the project directory `dir`, if there is one.
+    pub fn load(dir: &Path) -> Result<Option<Journal>> {
+        let path = dir.join(JOURNAL_DIR).join(JOURNAL_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let journal = fs::read_to_string(&path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+        let journal = serde_json::from_str(&journal)
+            .with_context(|| format!("could not parse {}", path.display()))?;
+
+        Ok(Some(journal))
+    }
+
+    /// Saves the journal in the project directory `dir`, replacing any existing journal.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let journal_dir = dir.join(JOURNAL_DIR);
+        fs::create_dir_all(&journal_dir)
+            .with_context(|| format!("could not create {}", journal_dir.display()))?;
+
+        // The journal is only useful locally, so keep it out of version control.
+        fs::write(journal_dir.join(".gitignore"), "*\n")?;
+
+        let path = journal_dir.join(JOURNAL_FILE);
+        write_atomic(&path, &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Removes the journal saved in the project directory `dir`.
+    pub fn remove(dir: &Path) -> Result<()> {
+        let path = dir.join(JOURNAL_DIR).join(JOURNAL_FILE);
+        fs::remove_file(&path).with_context(|| format!("could not remove {}", path.display()))
+    }
+
+    /// Reverts each change in the journal, most recent first.
+    ///
+    /// Files that have been modified since they were written are left alone, unless `force` is
+    /// true.
+    pub fn undo<F>(&self, force: bool, mut on_revert: F) -> Result<()>
+    where
+        F: FnMut(Reverted),
+    {
+        for entry in self.entries.iter().rev() {
+            let path = &entry.path;
+
+            let current = match fs::read_to_string(path) {
+                Ok(current) => Some(current),
+                Err(_) if !path.exists() => None,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("could not read {}", path.display()))
+                }
+            };
+
+            if !force && current != entry.written {
+                on_revert(Reverted::Modified(path));
+                continue;
+            }
+
+            match &entry.original {
+                Some(original) => {
+                    write_atomic(path, original)?;
+                    on_revert(Reverted::Restored(path));
+                }
+                None => {
+                    if current.is_some() {
+                        fs::remove_file(path)
+                            .with_context(|| format!("could not remove {}", path.display()))?;
+                    }
+                    on_revert(Reverted::Removed(path));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::{Journal, Plan};
+
+    #[test]
+    fn undo() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        let modified = dir.path().join("LICENSE-MIT");
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&license, "old")?;
+        fs::write(&manifest, "[package]\n")?;
+
+        let mut plan = Plan::new();
+        plan.write_file(&license, "new")
+            .write_file(&modified, "mit")
+            .update_manifest(&manifest, "package.license", "MIT");
+
+        let journal = plan.execute(|_| ())?;
+        journal.save(dir.path())?;
+        fs::write(&modified, "changed by hand")?;
+
+        let journal = Journal::load(dir.path())?.unwrap();
+        journal.undo(false, |_| ())?;
+
+        assert_eq!(fs::read_to_string(&license)?, "old");
+        assert_eq!(fs::read_to_string(&modified)?, "changed by hand");
+        assert_eq!(fs::read_to_string(&manifest)?, "[package]\n");
+
+        journal.undo(true, |_| ())?;
+        assert!(!modified.exists());
+
+        Ok(())
+    }
+}
@@ -1,13 +1,60 @@
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, bail, Result};
-use chrono::{Datelike, Local};
-use handlebars::Handlebars;
+use log::debug;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+
+pub use handlebars;
+
+pub use crate::applier::{
+    plan_cargo_manifest, plan_docs_license, plan_license_files, plan_package_manifests,
+    plan_path_licenses, project_name, Applier,
+};
+pub use crate::attributions::{
+    ATTRIBUTIONS_FILE, ATTRIBUTIONS_HTML_TEMPLATE, ATTRIBUTIONS_TEMPLATE,
+};
+pub use crate::config::{Config, UserConfig, CONFIG_FILE, DEFAULT_DOCS_DIR, USER_CONFIG_FILE};
+pub use crate::detect::{detect_license, Detection};
+pub use crate::expr::Expr;
+pub use crate::header::{
+    comment_style, has_header, header_license, insert_header, plan_headers, source_files,
+    with_header, CommentStyle, WalkOptions, IGNORE_FILE,
+};
+pub use crate::journal::{Journal, JournalEntry, Reverted, JOURNAL_DIR};
+pub use crate::metadata::{Condition, Copyleft, Limitation, Metadata, Permission};
+pub use crate::migrate::{migrate_license_files, nonstandard_license_files};
+pub use crate::plan::{Action, Event, ExecuteOptions, Plan};
+pub use crate::policy::{Evaluation, Policy};
+pub use crate::readme::{has_license_section, readme_docs_paragraph, readme_section};
+pub use crate::render::{CopyrightSymbol, FileNameStyle, HolderStyle, Renderer};
+pub use crate::report::{FileChange, FileStatus, ManifestChange, Report};
+pub use crate::third_party::{
+    plan_third_party_licenses, third_party_licenses, Dependency, THIRD_PARTY_DIR,
+    THIRD_PARTY_LICENSES,
+};
+
+mod applier;
+mod attributions;
+mod config;
+mod detect;
+mod expr;
+mod header;
+mod journal;
+mod metadata;
+mod migrate;
+mod plan;
+mod policy;
+mod readme;
+mod render;
+mod report;
+mod third_party;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 static LICENSES: Lazy<Vec<License>> = Lazy::new(|| {
     let licenses_toml = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/licenses.toml"));
@@ -19,91 +66,402 @@ static LICENSES: Lazy<Vec<License>> = Lazy::new(|| {
 });
 
 /// An open-source license.
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[non_exhaustive]
 pub struct License {
     /// The identifier for the license on the command line, if multiple licenses are present.
     ///
     /// For example, `LICENSE-APACHE` vs `LICENSE-MIT`.
     pub identifier: String,
 
-    /// The [SPDX license identifier](https://github.com/spdx/license-list-data/tree/v2.4).
+    /// The [SPDX license identifier](https://spdx.org/licenses/).
     pub spdx: String,
 
     /// A handlebars template of the license text.
     pub text: String,
+
+    /// A handlebars template of the copyright line, which the license text may include as
+    /// `{{copyright}}`. This may be overridden with [`Renderer::copyright`].
+    #[serde(default = "default_copyright")]
+    pub copyright: String,
+
+    /// What the license permits, requires, and limits.
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+/// The copyright line used by licenses that don't specify their own.
+pub const DEFAULT_COPYRIGHT: &str =
+    "Copyright {{copyright_symbol}} {{#if year}}{{year}} {{/if}}{{copyright_holders}}";
+
+fn default_copyright() -> String {
+    String::from(DEFAULT_COPYRIGHT)
+}
+
+impl License {
+    /// Creates a new license from its SPDX identifier, file identifier, and a handlebars template
+    /// of its text.
+    ///
+    /// The template may refer to the `year`, `copyright_holders`, `authors`, `copyright`, and
+    /// `copyright_symbol` variables, as well as any variables added with [`Renderer::var`] and the
+    /// helpers described in [`Renderer`]. The copyright line defaults to [`DEFAULT_COPYRIGHT`].
+    pub fn new(
+        spdx: impl Into<String>,
+        identifier: impl Into<String>,
+        text: impl Into<String>,
+    ) -> License {
+        License {
+            identifier: identifier.into(),
+            spdx: spdx.into(),
+            text: text.into(),
+            copyright: default_copyright(),
+            metadata: Metadata::default(),
+        }
+    }
+}
+
+static EXCEPTIONS: Lazy<Vec<Exception>> = Lazy::new(|| {
+    let exceptions_toml = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/exceptions.toml"));
+
+    let mut exceptions: BTreeMap<String, Vec<Exception>> =
+        toml_edit::easy::from_str(exceptions_toml).unwrap();
+
+    exceptions.remove("exception").unwrap()
+});
+
+/// An exception to a license, such as `LLVM-exception`, which grants additional permissions.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[non_exhaustive]
+pub struct Exception {
+    /// The SPDX identifier of the exception.
+    pub spdx: String,
+
+    /// The text of the exception. Unlike license texts, this is not a template.
+    pub text: String,
+
+    /// The SPDX identifiers of the licenses that the exception is usually combined with, such as
+    /// `Apache-2.0` for `LLVM-exception`.
+    #[serde(default)]
+    pub licenses: Vec<String>,
+}
+
+impl Exception {
+    /// Creates a new exception from its SPDX identifier and text.
+    pub fn new(spdx: impl Into<String>, text: impl Into<String>) -> Exception {
+        Exception {
+            spdx: spdx.into(),
+            text: text.into(),
+            licenses: vec![],
+        }
+    }
+}
+
+/// The SPDX identifier of the built-in license for proprietary software, which simply reserves
+/// all rights. It may be written as `proprietary`.
+pub const PROPRIETARY: &str = "LicenseRef-Proprietary";
+
+/// Expands an alias for a license expression, such as `proprietary` for [`PROPRIETARY`]. Other
+/// expressions are returned unchanged.
+pub fn resolve_alias(license_expr: &str) -> String {
+    if license_expr.trim().eq_ignore_ascii_case("proprietary") {
+        String::from(PROPRIETARY)
+    } else {
+        license_expr.to_owned()
+    }
 }
 
 /// Parses author names from a list of author names, which might include git-style author names
 /// such as `John Doe <jd@example.com>`.
+///
+/// Duplicate authors are removed, as with [`dedup_authors`].
 pub fn parse_author_names<'a>(authors: &[&'a str]) -> Result<Vec<&'a str>> {
     if authors.is_empty() {
         bail!("at least one author is required");
     }
 
-    let names = authors
-        .iter()
-        .map(|author| match parse_git_style_author(author) {
-            Some(name) => name,
-            None => author,
-        })
+    let names = dedup_authors(authors)
+        .into_iter()
+        .map(|author| Author::parse(author).name)
         .collect();
 
     Ok(names)
 }
 
-/// Returns true if the given license ID is known by SPDX 2.4.
-fn is_valid_spdx_id(id: &str) -> bool {
-    #[derive(Debug, Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct LicenseList {
-        licenses: Vec<License>,
+/// Removes duplicates from a list of authors that may have come from several sources, keeping the
+/// first of each.
+///
+/// Authors are duplicates if their names are the same apart from case, such as `Jane Doe` and
+/// `jane doe <jane@example.com>`, or if they have the same email address. Entries that list
+/// several authors, such as `Jane Doe <jane@example.com>, John Doe <john@example.com>`, are split
+/// into one author each, as with [`parse_authors`].
+pub fn dedup_authors<'a>(authors: &[&'a str]) -> Vec<&'a str> {
+    let mut names = vec![];
+    let mut emails = vec![];
+    let mut deduped = vec![];
+
+    for author in authors.iter().flat_map(|author| split_authors(author)) {
+        let (name, email) = match parse_git_style_author(author) {
+            Some((name, email)) => (name, Some(email.to_lowercase())),
+            None => (author, None),
+        };
+        let name = name.trim().to_lowercase();
+
+        let duplicate =
+            names.contains(&name) || email.as_ref().is_some_and(|email| emails.contains(email));
+
+        // Remember the duplicate's name and email as well, since they refer to the same person.
+        names.push(name);
+        emails.extend(email);
+
+        if !duplicate {
+            deduped.push(author);
+        }
     }
 
-    #[derive(Debug, Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct License {
-        license_id: String,
+    deduped
+}
+
+/// An author, such as `John Doe <jd@example.com>`, split into a name and email address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Author<'a> {
+    /// The name of the author.
+    pub name: &'a str,
+
+    /// The email address of the author, if one was given.
+    pub email: Option<&'a str>,
+}
+
+impl<'a> Author<'a> {
+    /// Parses a single author, which may be a git-style author with an email address, such as
+    /// `John Doe <jd@example.com>`.
+    pub fn parse(author: &'a str) -> Author<'a> {
+        let author = author.trim();
+        match parse_git_style_author(author) {
+            Some((name, email)) => Author {
+                name: name.trim(),
+                email: Some(email),
+            },
+            None => Author {
+                name: author,
+                email: None,
+            },
+        }
     }
+}
 
-    static SPDX_LICENSE_LIST: Lazy<LicenseList> = Lazy::new(|| {
-        serde_json::from_str(include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/src/spdx-licenses.json"
-        )))
-        .unwrap()
-    });
+impl fmt::Display for Author<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.email {
+            Some(email) => write!(f, "{} <{}>", self.name, email),
+            None => f.write_str(self.name),
+        }
+    }
+}
+
+/// Parses a string of one or more authors, such as the
+/// `Jane Doe <jane@example.com>, John Doe <john@example.com>` that some manifests list as a single
+/// author.
+///
+/// The string is only split at commas if it has more than one email address, so that names with
+/// commas, like `Example, Inc.`, are kept whole.
+///
+/// # Example
+///
+/// ```
+/// use apply_license::{parse_authors, Author};
+///
+/// assert_eq!(
+///     parse_authors("Example, Inc."),
+///     [Author { name: "Example, Inc.", email: None }],
+/// );
+/// assert_eq!(
+///     parse_authors("Jane Doe <jane@example.com>, John Doe <john@example.com>"),
+///     [
+///         Author { name: "Jane Doe", email: Some("jane@example.com") },
+///         Author { name: "John Doe", email: Some("john@example.com") },
+///     ],
+/// );
+/// ```
+pub fn parse_authors(authors: &str) -> Vec<Author<'_>> {
+    split_authors(authors)
+        .into_iter()
+        .map(Author::parse)
+        .collect()
+}
+
+/// Splits a string listing several authors into each author, at the commas that aren't part of an
+/// email address.
+fn split_authors(authors: &str) -> Vec<&str> {
+    if authors.matches('<').count() < 2 {
+        return vec![authors.trim()];
+    }
+
+    let mut split = vec![];
+    let mut start = 0;
+    let mut in_email = false;
+    for (i, c) in authors.char_indices() {
+        match c {
+            '<' => in_email = true,
+            '>' => in_email = false,
+            ',' if !in_email => {
+                split.push(authors[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    split.push(authors[start..].trim());
+
+    split.retain(|author| !author.is_empty());
+    split
+}
+
+/// Returns an iterator over the licenses that this crate is able to render.
+pub fn supported_licenses() -> impl Iterator<Item = &'static License> {
+    LICENSES.iter()
+}
+
+/// Returns an iterator over the license exceptions whose texts are included with this crate.
+pub fn supported_exceptions() -> impl Iterator<Item = &'static Exception> {
+    EXCEPTIONS.iter()
+}
+
+/// An entry in the SPDX license list.
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SpdxLicense {
+    /// The SPDX license identifier, such as `MIT`.
+    pub id: String,
+
+    /// The full name of the license, such as `MIT License`.
+    pub name: String,
+
+    /// Whether the license is approved by the Open Source Initiative.
+    pub is_osi_approved: bool,
 
-    SPDX_LICENSE_LIST
-        .licenses
+    /// Whether the identifier has been deprecated by SPDX.
+    pub is_deprecated_license_id: bool,
+}
+
+static SPDX_LICENSES: Lazy<Vec<SpdxLicense>> = Lazy::new(|| {
+    use spdx::identifiers::{IS_DEPRECATED, IS_OSI_APPROVED, LICENSES};
+
+    LICENSES
         .iter()
-        .any(|license| license.license_id == id)
+        .map(|&(id, name, flags)| SpdxLicense {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            is_osi_approved: flags & IS_OSI_APPROVED != 0,
+            is_deprecated_license_id: flags & IS_DEPRECATED != 0,
+        })
+        .collect()
+});
+
+/// Returns an iterator over every license in the SPDX license list, including those that are not
+/// supported by this crate.
+pub fn spdx_licenses() -> impl Iterator<Item = &'static SpdxLicense> {
+    SPDX_LICENSES.iter()
+}
+
+/// Looks up a license in the SPDX license list by its identifier.
+pub fn spdx_license(id: &str) -> Option<&'static SpdxLicense> {
+    SPDX_LICENSES.iter().find(|license| license.id == id)
+}
+
+/// Returns true if the given license ID is in the SPDX license list.
+///
+/// Note that a valid ID is not necessarily supported. See [`supported_licenses`] for the list of
+/// licenses that can be rendered.
+pub fn is_valid_spdx_id(id: &str) -> bool {
+    spdx_license(id).is_some()
+}
+
+/// Looks up a supported license by its SPDX identifier.
+///
+/// The GNU licenses (GPL, LGPL, and AGPL) have the same text whether they're applied as `-only` or
+/// `-or-later` (or with the older `+` suffix), so `GPL-3.0-or-later` and `GPL-3.0+` both find the
+/// `GPL-3.0` license.
+pub fn supported_license(id: &str) -> Option<&'static License> {
+    let id = match id
+        .strip_suffix('+')
+        .or_else(|| id.strip_suffix("-or-later"))
+        .or_else(|| id.strip_suffix("-only"))
+    {
+        Some(base)
+            if ["GPL-", "LGPL-", "AGPL-"]
+                .iter()
+                .any(|gnu| base.starts_with(gnu)) =>
+        {
+            base
+        }
+        _ => id,
+    };
+
+    LICENSES.iter().find(|license| license.spdx == id)
 }
 
 /// Parse a list of license identifiers from an SPDX license expression.
 ///
 /// The cargo manifest format allows combining license expressions with `/`, so we allow it as
 /// well, though it's not valid SPDX.
+///
+/// Each license is only listed once, even if the expression names it more than once, such as in
+/// `GPL-3.0-only OR GPL-3.0-or-later`.
 pub fn parse_spdx(license_expr: &str) -> Result<Vec<&'static License>> {
-    let split: Box<dyn Iterator<Item = &str>> = if license_expr.contains('/') {
-        Box::new(license_expr.split('/'))
-    } else {
-        Box::new(license_expr.split_whitespace())
-    };
+    let mut licenses: Vec<&'static License> = vec![];
 
-    split
-        .flat_map(|token| match token {
-            "WITH" | "OR" | "AND" => None,
-            token => Some(token),
-        })
-        .map(|id| {
-            if is_valid_spdx_id(id) {
-                LICENSES
-                    .iter()
-                    .find(|license| license.spdx == id)
-                    .ok_or_else(|| anyhow!("SPDX ID '{}' is valid, but unsupported by this program. Please open a PR!", id))
-            } else {
-                Err(anyhow!("invalid SPDX license ID: {}", id))
-            }
+    for id in Expr::parse(license_expr)?.licenses() {
+        debug!("resolving SPDX ID '{}'", id);
+
+        let license = if let Some(license) = supported_license(id) {
+            license
+        } else if is_valid_spdx_id(id) {
+            bail!(
+                "SPDX ID '{}' is valid, but unsupported by this program. Please open a PR!",
+                id
+            );
+        } else {
+            bail!("no license text is available for '{}'", id);
+        };
+
+        if !licenses.iter().any(|seen| seen.spdx == license.spdx) {
+            licenses.push(license);
+        }
+    }
+
+    Ok(licenses)
+}
+
+/// Parse the exceptions in an SPDX license expression, such as `LLVM-exception` in
+/// `Apache-2.0 WITH LLVM-exception`.
+///
+/// Returns the SPDX identifier of each license that has an exception, along with the exception.
+pub fn parse_exceptions(license_expr: &str) -> Result<Vec<(String, &'static Exception)>> {
+    let expr = Expr::parse(license_expr)?;
+
+    let mut exceptions = vec![];
+    expr.walk(&mut |expr| {
+        if let Expr::With { license, exception } = expr {
+            exceptions.push((license.clone(), exception.clone()));
+        }
+    });
+
+    exceptions
+        .into_iter()
+        .map(|(license, id)| {
+            debug!("resolving SPDX exception '{}'", id);
+
+            let exception = EXCEPTIONS
+                .iter()
+                .find(|exception| exception.spdx == id)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "license exception '{}' is unsupported by this program. Please open a PR!",
+                        id
+                    )
+                })?;
+
+            Ok((license, exception))
         })
         .collect()
 }
@@ -112,57 +470,70 @@ pub fn parse_spdx(license_expr: &str) -> Result<Vec<&'static License>> {
 ///
 /// If only one license file is present, writes the file name will be `LICENSE`. If two or more
 /// licenses are present, then each file will be named `LICENSE-{id}` (e.g., `LICENSE-MIT`).
+///
+/// See [`Renderer`] to customize the template context.
 pub fn render_license_text<S: Borrow<str>>(
     licenses: &[&License],
     authors: &[S],
 ) -> Result<BTreeMap<PathBuf, String>> {
-    let mut reg = Handlebars::new();
-
-    for license in LICENSES.iter() {
-        reg.register_template_string(&license.spdx, &license.text)
-            .expect("syntax error in license template");
-    }
+    Renderer::new().render(licenses, authors)
+}
 
-    #[derive(Debug, Serialize)]
-    struct TemplateData {
-        year: i32,
-        copyright_holders: String,
-    }
+/// Returns the existing license files that would be left over after writing the rendered `files`.
+///
+/// For example, a lone `LICENSE` file is stale once a project moves to `LICENSE-MIT` and
+/// `LICENSE-APACHE`, and vice versa. Only `LICENSE` and `LICENSE-{id}` files for supported licenses
+/// in the same directory as the rendered files are considered, so files like `LICENSE-THIRD-PARTY`
+/// are left alone.
+pub fn stale_license_files(files: &BTreeMap<PathBuf, String>) -> Vec<PathBuf> {
+    let dir = files
+        .keys()
+        .next()
+        .and_then(|path| path.parent())
+        .unwrap_or_else(|| Path::new(""));
 
-    licenses
-        .iter()
-        .map(|license| {
-            let name = if licenses.len() == 1 {
-                String::from("LICENSE")
-            } else {
-                format!("LICENSE-{}", license.identifier)
-            };
-
-            let contents = reg.render(
-                &license.spdx,
-                &TemplateData {
-                    year: Local::today().year(),
-                    copyright_holders: authors.join(", "),
-                },
-            )?;
+    let candidates = std::iter::once(String::from("LICENSE"))
+        .chain(supported_licenses().map(|license| format!("LICENSE-{}", license.identifier)));
 
-            Ok((PathBuf::from(name), contents))
-        })
+    candidates
+        .map(|name| dir.join(name))
+        .filter(|path| !files.contains_key(path) && path.is_file())
         .collect()
 }
 
-fn parse_git_style_author(name: &str) -> Option<&str> {
+/// Returns `dir`, or `.` if it's empty, as it is for the current directory, so that it can be read
+/// or passed to git.
+#[doc(hidden)]
+pub fn existing_dir(dir: &Path) -> &Path {
+    if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    }
+}
+
+/// Splits a git-style author, such as `John Doe <jd@example.com>`, into its name and email.
+fn parse_git_style_author(name: &str) -> Option<(&str, &str)> {
     static GIT_NAME_RE: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"(?P<name>.+) <(?P<email>.+)>").unwrap());
 
-    GIT_NAME_RE
-        .captures(name)
-        .map(|caps| caps.name("name").unwrap().as_str())
+    GIT_NAME_RE.captures(name).map(|caps| {
+        (
+            caps.name("name").unwrap().as_str(),
+            caps.name("email").unwrap().as_str(),
+        )
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{is_valid_spdx_id, parse_spdx, License, LICENSES};
+    use std::path::Path;
+
+    use crate::{
+        dedup_authors, is_valid_spdx_id, parse_author_names, parse_authors, parse_exceptions,
+        parse_spdx, render_license_text, resolve_alias, spdx_license, supported_license,
+        supported_licenses, Author, License, LICENSES, PROPRIETARY,
+    };
 
     fn get_license(id: &str) -> &'static License {
         LICENSES.iter().find(|l| l.spdx == id).unwrap()
@@ -173,6 +544,19 @@ mod tests {
         assert!(LICENSES.iter().any(|l| l.spdx == "MIT"));
     }
 
+    #[test]
+    fn supported_licenses_are_valid() {
+        assert!(supported_licenses()
+            .all(|l| l.spdx.starts_with("LicenseRef-") || is_valid_spdx_id(&l.spdx)));
+    }
+
+    #[test]
+    fn spdx_license_metadata() {
+        let mit = spdx_license("MIT").unwrap();
+        assert_eq!(mit.name, "MIT License");
+        assert!(mit.is_osi_approved);
+    }
+
     #[test]
     fn valid_spdx_ids() {
         assert!(is_valid_spdx_id("MIT"));
@@ -199,4 +583,163 @@ mod tests {
             &[get_license("MIT"), get_license("Apache-2.0")]
         );
     }
+
+    #[test]
+    fn proprietary() {
+        let expr = resolve_alias("proprietary");
+        assert_eq!(expr, PROPRIETARY);
+        assert_eq!(resolve_alias("MIT"), "MIT");
+
+        let licenses = parse_spdx(&expr).unwrap();
+        let files = render_license_text(&licenses, &["Example Inc."]).unwrap();
+        let text = &files[Path::new("LICENSE")];
+        assert!(text.starts_with("Copyright (c) "));
+        assert!(text.contains("Example Inc.\nAll rights reserved.\n"));
+    }
+
+    #[test]
+    fn exceptions() {
+        let exceptions = parse_exceptions("Apache-2.0 WITH LLVM-exception OR MIT").unwrap();
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions[0].0, "Apache-2.0");
+        assert_eq!(exceptions[0].1.spdx, "LLVM-exception");
+
+        assert!(parse_exceptions("GPL-3.0 WITH foobar").is_err());
+    }
+
+    #[test]
+    fn render_custom_license() {
+        let license = License::new(
+            "LicenseRef-Custom",
+            "CUSTOM",
+            "Copyright {{copyright_holders}}",
+        );
+        let files = render_license_text(&[&license], &["John Doe"]).unwrap();
+        assert_eq!(files[Path::new("LICENSE")], "Copyright John Doe");
+    }
+
+    #[test]
+    fn dedup() {
+        assert_eq!(
+            dedup_authors(&[
+                "Jane Doe",
+                "jane doe <jane@example.com>",
+                "J. Doe <JANE@example.com>",
+                "John Doe <jane@example.org>",
+            ]),
+            ["Jane Doe", "John Doe <jane@example.org>"]
+        );
+        assert_eq!(
+            dedup_authors(&["J. Doe <jane@example.com>", "Jane Doe <Jane@Example.com>"]),
+            ["J. Doe <jane@example.com>"]
+        );
+        assert_eq!(
+            parse_author_names(&["Jane Doe <jane@example.com>", "JANE DOE"]).unwrap(),
+            ["Jane Doe"]
+        );
+        assert_eq!(
+            parse_author_names(&["Jane Doe <jane@example.com>, John Doe <john@example.com>"])
+                .unwrap(),
+            ["Jane Doe", "John Doe"]
+        );
+    }
+
+    #[test]
+    fn multiple_authors() {
+        assert_eq!(
+            parse_authors("Jane Doe <jane@example.com>, John Doe <john@example.com>, Acme"),
+            [
+                Author {
+                    name: "Jane Doe",
+                    email: Some("jane@example.com"),
+                },
+                Author {
+                    name: "John Doe",
+                    email: Some("john@example.com"),
+                },
+                Author {
+                    name: "Acme",
+                    email: None,
+                },
+            ]
+        );
+        assert_eq!(
+            parse_authors("John Doe <john@example.com>")[0].to_string(),
+            "John Doe <john@example.com>"
+        );
+        assert_eq!(parse_authors("Example, Inc.").len(), 1);
+    }
+
+    #[test]
+    fn ofl() {
+        let licenses = parse_spdx("OFL-1.1").unwrap();
+        let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+        let text = &files[Path::new("LICENSE")];
+        assert!(text.starts_with("Copyright (c) "));
+        assert!(text.contains("John Doe\n\nThis Font Software is licensed"));
+
+        // The reserved font names are up to the font's authors, so they're left to be added by
+        // hand.
+        assert!(!text.contains("with Reserved Font Name"));
+    }
+
+    #[test]
+    fn public_domain() {
+        let licenses = parse_spdx("Unlicense").unwrap();
+        let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+        let text = &files[Path::new("LICENSE")];
+        assert!(text.starts_with("This is free and unencumbered software"));
+        assert!(!text.contains("John Doe"));
+
+        let licenses = parse_spdx("CC0-1.0").unwrap();
+        let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+        let text = &files[Path::new("LICENSE")];
+        assert!(text.starts_with("Creative Commons Legal Code\n\nCC0 1.0 Universal\n"));
+        assert!(!text.contains("John Doe"));
+    }
+
+    #[test]
+    fn gnu_suffixes() {
+        let agpl = get_license("AGPL-3.0");
+        for id in [
+            "AGPL-3.0",
+            "AGPL-3.0+",
+            "AGPL-3.0-only",
+            "AGPL-3.0-or-later",
+        ] {
+            assert_eq!(parse_spdx(id).unwrap(), &[agpl]);
+        }
+
+        assert_eq!(
+            parse_spdx("GPL-3.0-or-later").unwrap(),
+            &[get_license("GPL-3.0")]
+        );
+        assert_eq!(
+            parse_spdx("GPL-2.0-only OR GPL-2.0").unwrap(),
+            &[get_license("GPL-2.0")]
+        );
+        assert_eq!(
+            parse_spdx("GPL-3.0-or-later OR GPL-3.0-only").unwrap(),
+            &[get_license("GPL-3.0")]
+        );
+        assert!(parse_spdx("MIT-only").is_err());
+        assert!(supported_license("MIT-only").is_none());
+        assert!(supported_license("MIT+").is_none());
+    }
+
+    #[test]
+    fn lgpl() {
+        let licenses = parse_spdx("LGPL-2.1-or-later").unwrap();
+        let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+        assert_eq!(files.keys().collect::<Vec<_>>(), [Path::new("LICENSE")]);
+
+        // The LGPL 3.0 refers to the GPL 3.0 for most of its terms, so both are included.
+        let licenses = parse_spdx("LGPL-3.0-only").unwrap();
+        let files = render_license_text(&licenses, &["John Doe"]).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [Path::new("LICENSE-GPL"), Path::new("LICENSE-LGPL-3.0")]
+        );
+        assert!(files[Path::new("LICENSE-GPL")].contains("GNU GENERAL PUBLIC LICENSE"));
+    }
 }
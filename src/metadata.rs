@@ -0,0 +1,181 @@
+//! What licenses permit and require, following the categories used by
+//! [choosealicense.com](https://choosealicense.com/appendix/).
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// What a license permits, requires, and limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Metadata {
+    /// What the license allows.
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+
+    /// What the license requires in exchange.
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+
+    /// What the license doesn't provide.
+    #[serde(default)]
+    pub limitations: Vec<Limitation>,
+
+    /// How strongly the license requires derivative works to use the same license.
+    #[serde(default)]
+    pub copyleft: Copyleft,
+}
+
+/// Something that a license allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Permission {
+    /// The software may be used for commercial purposes.
+    CommercialUse,
+
+    /// The software may be modified.
+    Modifications,
+
+    /// The software may be distributed.
+    Distribution,
+
+    /// The license grants the contributors' patent rights.
+    PatentUse,
+
+    /// The software may be used and modified privately.
+    PrivateUse,
+}
+
+/// Something that a license requires of those who use it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Condition {
+    /// The license and copyright notice must be included with the software.
+    IncludeCopyright,
+
+    /// Changes made to the software must be documented.
+    DocumentChanges,
+
+    /// The source code must be made available when the software is distributed.
+    DiscloseSource,
+
+    /// Users who interact with the software over a network must be able to get its source.
+    NetworkUseDisclose,
+
+    /// Modifications must be released under the same license.
+    SameLicense,
+}
+
+/// Something that a license explicitly doesn't provide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Limitation {
+    /// The contributors aren't liable for damages.
+    Liability,
+
+    /// The software comes without a warranty.
+    Warranty,
+
+    /// The license doesn't grant trademark rights.
+    TrademarkUse,
+
+    /// The license explicitly doesn't grant patent rights.
+    PatentUse,
+}
+
+/// How strongly a license requires derivative works to use the same license.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Copyleft {
+    /// Derivative works may use any license (a permissive license).
+    #[default]
+    None,
+
+    /// Modifications to the licensed files must use the same license, but larger works that use
+    /// them need not.
+    Weak,
+
+    /// Derivative works must use the same license.
+    Strong,
+
+    /// Like [`Copyleft::Strong`], and providing the software over a network counts as
+    /// distribution.
+    Network,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Permission::CommercialUse => "Commercial use",
+            Permission::Modifications => "Modification",
+            Permission::Distribution => "Distribution",
+            Permission::PatentUse => "Patent use",
+            Permission::PrivateUse => "Private use",
+        })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Condition::IncludeCopyright => "License and copyright notice",
+            Condition::DocumentChanges => "State changes",
+            Condition::DiscloseSource => "Disclose source",
+            Condition::NetworkUseDisclose => "Network use is distribution",
+            Condition::SameLicense => "Same license",
+        })
+    }
+}
+
+impl fmt::Display for Limitation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Limitation::Liability => "Liability",
+            Limitation::Warranty => "Warranty",
+            Limitation::TrademarkUse => "Trademark use",
+            Limitation::PatentUse => "Patent use",
+        })
+    }
+}
+
+impl fmt::Display for Copyleft {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Copyleft::None => "none (permissive)",
+            Copyleft::Weak => "weak",
+            Copyleft::Strong => "strong",
+            Copyleft::Network => "network",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, Copyleft, Metadata, Permission};
+    use crate::supported_licenses;
+
+    #[test]
+    fn supported_licenses_have_metadata() {
+        for license in supported_licenses() {
+            assert!(
+                license.metadata != Metadata::default(),
+                "{} has no metadata",
+                license.spdx
+            );
+        }
+    }
+
+    #[test]
+    fn gpl() {
+        let gpl = supported_licenses()
+            .find(|license| license.spdx == "GPL-3.0")
+            .unwrap();
+        assert!(gpl.metadata.permissions.contains(&Permission::PatentUse));
+        assert!(gpl.metadata.conditions.contains(&Condition::SameLicense));
+        assert_eq!(gpl.metadata.copyleft, Copyleft::Strong);
+    }
+}
@@ -0,0 +1,226 @@
+//! Renaming license files with nonstandard names, such as `license.txt` or `MIT-LICENSE`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{supported_licenses, License, Plan};
+
+/// Extensions that are stripped from license file names before they are parsed.
+const EXTENSIONS: &[&str] = &["txt", "md", "markdown", "rst"];
+
+/// A license file found in a project directory.
+#[derive(Debug, Clone, PartialEq)]
+struct LicenseFile {
+    path: PathBuf,
+
+    /// The license named by the file, such as MIT for `MIT-LICENSE`, if any.
+    license: Option<&'static License>,
+
+    /// Whether the file is already named `LICENSE` or `LICENSE-{id}`.
+    standard: bool,
+}
+
+/// Returns the license files in `dir` that don't follow the `LICENSE` or `LICENSE-{id}` naming
+/// convention, such as `license.txt`, `LICENCE`, or `MIT-LICENSE`.
+pub fn nonstandard_license_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    Ok(license_files(dir)?
+        .into_iter()
+        .filter(|file| !file.standard)
+        .map(|file| file.path)
+        .collect())
+}
+
+/// Returns a plan that renames the license files in `dir` to `LICENSE` (if there is only one
+/// license) or `LICENSE-{id}` (if there are several), preserving their contents.
+///
+/// Files that would be renamed to the same name are consolidated if their contents are identical.
+/// Otherwise, the plan includes a warning and the file is left alone.
+pub fn migrate_license_files(dir: &Path) -> Result<Plan> {
+    let files = license_files(dir)?;
+
+    let single = files
+        .iter()
+        .filter_map(|file| file.license.map(|license| &license.spdx))
+        .collect::<BTreeSet<_>>()
+        .len()
+        <= 1;
+
+    let mut targets: BTreeMap<PathBuf, Vec<&LicenseFile>> = BTreeMap::new();
+    let mut plan = Plan::new();
+
+    for file in &files {
+        let name = match file.license {
+            _ if single => String::from("LICENSE"),
+            Some(license) => format!("LICENSE-{}", license.identifier),
+            None => {
+                if !file.standard {
+                    plan.warn(format!(
+                        "could not tell which license {} is for, so it was not renamed",
+                        file.path.display()
+                    ));
+                }
+                continue;
+            }
+        };
+
+        targets.entry(dir.join(name)).or_default().push(file);
+    }
+
+    for (target, mut sources) in targets {
+        // Prefer a file that already has the right name, so that it isn't touched.
+        sources.sort_by_key(|file| file.path != target);
+
+        let kept = sources[0];
+        let contents = fs::read_to_string(&kept.path)
+            .with_context(|| format!("could not read {}", kept.path.display()))?;
+
+        if kept.path != target {
+            plan.remove_file(&kept.path).write_file(&target, &contents);
+        }
+
+        for duplicate in &sources[1..] {
+            let duplicate_contents = fs::read_to_string(&duplicate.path)
+                .with_context(|| format!("could not read {}", duplicate.path.display()))?;
+
+            if duplicate_contents == contents {
+                plan.remove_file(&duplicate.path);
+            } else {
+                plan.warn(format!(
+                    "{} and {} would both be named {}, but their contents differ, so {} was not \
+                     renamed",
+                    kept.path.display(),
+                    duplicate.path.display(),
+                    target.display(),
+                    duplicate.path.display()
+                ));
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Finds the license files in `dir`, sorted by path.
+fn license_files(dir: &Path) -> Result<Vec<LicenseFile>> {
+    let entries = fs::read_dir(crate::existing_dir(dir))
+        .with_context(|| format!("could not read directory {}", dir.display()))?;
+
+    let mut files = vec![];
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        if let Some(name) = entry.file_name().to_str() {
+            if let Some((license, standard)) = parse_license_file_name(name) {
+                files.push(LicenseFile {
+                    path: dir.join(name),
+                    license,
+                    standard,
+                });
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Parses a file name like `LICENSE`, `licence.txt`, or `MIT-LICENSE`, returning the license it
+/// names, if any, and whether the name is standard.
+///
+/// Returns `None` if the file isn't a license file, or names a license that isn't supported, such
+/// as `LICENSE-THIRD-PARTY`.
+fn parse_license_file_name(name: &str) -> Option<(Option<&'static License>, bool)> {
+    static LICENSE_FILE_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?i)^(?:(?P<prefix>.+?)[-_.])?licen[cs]e(?:[-_.](?P<suffix>.+))?$").unwrap()
+    });
+
+    let stem = match name.rsplit_once('.') {
+        Some((stem, ext)) if EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)) => stem,
+        _ => name,
+    };
+
+    let caps = LICENSE_FILE_RE.captures(stem)?;
+
+    let id = match (caps.name("prefix"), caps.name("suffix")) {
+        (None, None) => return Some((None, name == "LICENSE")),
+        (Some(id), None) | (None, Some(id)) => id.as_str(),
+        (Some(_), Some(_)) => return None,
+    };
+
+    let license = supported_licenses().find(|license| {
+        license.identifier.eq_ignore_ascii_case(id) || license.spdx.eq_ignore_ascii_case(id)
+    })?;
+
+    Some((
+        Some(license),
+        name == format!("LICENSE-{}", license.identifier),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use super::{migrate_license_files, parse_license_file_name};
+    use crate::Action;
+
+    #[test]
+    fn parse_file_names() {
+        let identifier = |name| {
+            parse_license_file_name(name)
+                .map(|(license, standard)| (license.map(|l| l.identifier.as_str()), standard))
+        };
+
+        assert_eq!(identifier("LICENSE"), Some((None, true)));
+        assert_eq!(identifier("license.txt"), Some((None, false)));
+        assert_eq!(identifier("LICENCE"), Some((None, false)));
+        assert_eq!(identifier("LICENSE-MIT"), Some((Some("MIT"), true)));
+        assert_eq!(identifier("MIT-LICENSE"), Some((Some("MIT"), false)));
+        assert_eq!(
+            identifier("license_apache.md"),
+            Some((Some("APACHE"), false))
+        );
+        assert_eq!(identifier("LICENSE-THIRD-PARTY"), None);
+        assert_eq!(identifier("README.md"), None);
+    }
+
+    #[test]
+    fn migrate() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = dir.path();
+        fs::write(dir.join("MIT-LICENSE"), "mit")?;
+        fs::write(dir.join("license-apache.txt"), "apache")?;
+        fs::write(dir.join("LICENSE-APACHE"), "apache")?;
+
+        let plan = migrate_license_files(dir)?;
+
+        assert_eq!(
+            plan.actions,
+            &[
+                Action::RemoveFile {
+                    path: dir.join("license-apache.txt")
+                },
+                Action::RemoveFile {
+                    path: dir.join("MIT-LICENSE")
+                },
+                Action::WriteFile {
+                    path: dir.join("LICENSE-MIT"),
+                    contents: String::from("mit")
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
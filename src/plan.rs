@@ -0,0 +1,808 @@
+//! Planning and applying changes to a project.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use toml_edit::{Document, Item};
+
+use crate::Journal;
+
+/// A single change to a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Action {
+    /// Write a file with the given contents.
+    WriteFile {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The new contents of the file.
+        contents: String,
+    },
+
+    /// Remove a file, such as a license file left over from a different license layout.
+    RemoveFile {
+        /// The path of the file.
+        path: PathBuf,
+    },
+
+    /// Set a string field in a TOML or JSON manifest, such as `package.license` in `Cargo.toml` or
+    /// `license` in `package.json`.
+    UpdateManifest {
+        /// The path to the manifest.
+        path: PathBuf,
+
+        /// The dotted path to the field, such as `package.license`.
+        field: String,
+
+        /// The new value of the field.
+        value: String,
+    },
+
+    /// Remove a field from a TOML or JSON manifest, such as `package.license-file` in
+    /// `Cargo.toml`.
+    RemoveManifestField {
+        /// The path to the manifest.
+        path: PathBuf,
+
+        /// The dotted path to the field, such as `package.license-file`.
+        field: String,
+    },
+}
+
+impl Action {
+    /// Returns the path of the file that this action modifies.
+    pub fn path(&self) -> &Path {
+        match self {
+            Action::WriteFile { path, .. }
+            | Action::RemoveFile { path }
+            | Action::UpdateManifest { path, .. }
+            | Action::RemoveManifestField { path, .. } => path,
+        }
+    }
+
+    /// Returns true if the change that the action would make has already been made, such as a file
+    /// that already has the contents that would be written.
+    pub fn is_satisfied(&self) -> Result<bool> {
+        Ok(match self {
+            Action::WriteFile { path, contents } => {
+                read_if_exists(path)?.as_ref() == Some(contents)
+            }
+            Action::RemoveFile { path } => !path.exists(),
+            Action::UpdateManifest { path, field, value } if is_json(path) => {
+                json_pointer(&read_json_manifest(path)?, field).and_then(Value::as_str)
+                    == Some(value.as_str())
+            }
+            Action::UpdateManifest { path, field, value } => {
+                read_manifest(path)?.as_item().pointer(field) == Some(value.as_str())
+            }
+            Action::RemoveManifestField { path, field } if is_json(path) => {
+                json_pointer(&read_json_manifest(path)?, field).is_none()
+            }
+            Action::RemoveManifestField { path, field } => {
+                !read_manifest(path)?.as_item().contains(field)
+            }
+        })
+    }
+
+    /// Returns the current contents of the file that this action modifies (or `None` if it does
+    /// not exist), and the contents that it will have after the action is performed (or `None` if
+    /// it will be removed).
+    pub fn preview(&self) -> Result<(Option<String>, Option<String>)> {
+        match self {
+            Action::WriteFile { path, contents } => {
+                Ok((read_if_exists(path)?, Some(contents.clone())))
+            }
+            Action::RemoveFile { path } => Ok((read_if_exists(path)?, None)),
+            Action::UpdateManifest { path, field, value } => {
+                let original = fs::read_to_string(path)
+                    .with_context(|| format!("could not read {}", path.display()))?;
+                let updated = updated_manifest(path, &original, field, Some(value))?;
+                Ok((Some(original), Some(updated)))
+            }
+            Action::RemoveManifestField { path, field } => {
+                let original = fs::read_to_string(path)
+                    .with_context(|| format!("could not read {}", path.display()))?;
+                let updated = updated_manifest(path, &original, field, None)?;
+                Ok((Some(original), Some(updated)))
+            }
+        }
+    }
+}
+
+/// Options controlling how a [`Plan`] is executed.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ExecuteOptions {
+    /// Whether to copy each existing file to `<file>.bak` before modifying it.
+    pub backup: bool,
+
+    /// The project directory to save the journal in, if any.
+    pub journal_dir: Option<PathBuf>,
+}
+
+impl ExecuteOptions {
+    /// Creates the default options.
+    pub fn new() -> ExecuteOptions {
+        ExecuteOptions::default()
+    }
+
+    /// Sets whether to copy each existing file to `<file>.bak` before modifying it.
+    pub fn backup(&mut self, backup: bool) -> &mut ExecuteOptions {
+        self.backup = backup;
+        self
+    }
+
+    /// Saves the journal in the project directory `dir`, even if executing the plan fails partway,
+    /// so that the changes that were made can still be reverted.
+    pub fn journal_dir(&mut self, dir: impl Into<PathBuf>) -> &mut ExecuteOptions {
+        self.journal_dir = Some(dir.into());
+        self
+    }
+}
+
+/// Progress reported while executing a [`Plan`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A file will be written.
+    FilePlanned(&'a Path),
+
+    /// A file was written.
+    FileWritten {
+        /// The path of the file.
+        path: &'a Path,
+
+        /// Whether the file did not exist before it was written.
+        created: bool,
+
+        /// The contents that were written.
+        contents: &'a str,
+    },
+
+    /// A manifest field was updated.
+    ManifestUpdated {
+        /// The path to the manifest.
+        path: &'a Path,
+
+        /// The dotted path to the field.
+        field: &'a str,
+
+        /// The new value of the field.
+        value: &'a str,
+    },
+
+    /// A manifest field was removed.
+    ManifestFieldRemoved {
+        /// The path to the manifest.
+        path: &'a Path,
+
+        /// The dotted path to the field.
+        field: &'a str,
+    },
+
+    /// A file was removed.
+    FileRemoved(&'a Path),
+
+    /// A file was left alone, because it was already up to date.
+    Unchanged(&'a Path),
+
+    /// An existing file was copied before being modified.
+    BackedUp {
+        /// The path of the file.
+        path: &'a Path,
+
+        /// The path of the copy.
+        backup: &'a Path,
+    },
+
+    /// Something that the user should know about, but which does not prevent the plan from being
+    /// executed.
+    Warning(&'a str),
+}
+
+/// A set of changes to make to a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    /// The license expression that the plan applies, if any.
+    #[serde(default)]
+    pub license: Option<String>,
+
+    /// The actions to perform, in order.
+    pub actions: Vec<Action>,
+
+    /// Warnings collected while building the plan.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+
+    /// Problems with the project that the plan doesn't fix, such as source files that declare a
+    /// license that isn't part of the project's. A check fails if there are any.
+    #[serde(default)]
+    pub problems: Vec<String>,
+}
+
+impl Plan {
+    /// Creates an empty plan.
+    pub fn new() -> Plan {
+        Plan::default()
+    }
+
+    /// Records the license expression that the plan applies.
+    pub fn license(&mut self, license: impl Into<String>) -> &mut Plan {
+        self.license = Some(license.into());
+        self
+    }
+
+    /// Adds an action that writes `contents` to the file at `path`.
+    pub fn write_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        contents: impl Into<String>,
+    ) -> &mut Plan {
+        self.actions.push(Action::WriteFile {
+            path: path.into(),
+            contents: contents.into(),
+        });
+        self
+    }
+
+    /// Adds an action that removes the file at `path`.
+    pub fn remove_file(&mut self, path: impl Into<PathBuf>) -> &mut Plan {
+        self.actions.push(Action::RemoveFile { path: path.into() });
+        self
+    }
+
+    /// Adds an action that sets the dotted `field` of the TOML manifest at `path` to `value`.
+    pub fn update_manifest(
+        &mut self,
+        path: impl Into<PathBuf>,
+        field: impl Into<String>,
+        value: impl Into<String>,
+    ) -> &mut Plan {
+        self.actions.push(Action::UpdateManifest {
+            path: path.into(),
+            field: field.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Adds an action that removes the dotted `field` from the TOML manifest at `path`.
+    pub fn remove_manifest_field(
+        &mut self,
+        path: impl Into<PathBuf>,
+        field: impl Into<String>,
+    ) -> &mut Plan {
+        self.actions.push(Action::RemoveManifestField {
+            path: path.into(),
+            field: field.into(),
+        });
+        self
+    }
+
+    /// Moves the actions, warnings, and problems of another plan to the end of this one.
+    pub fn append(&mut self, other: Plan) -> &mut Plan {
+        self.actions.extend(other.actions);
+        self.warnings.extend(other.warnings);
+        self.problems.extend(other.problems);
+        self
+    }
+
+    /// Adds a warning to be reported when the plan is executed.
+    pub fn warn(&mut self, warning: impl Into<String>) -> &mut Plan {
+        self.warnings.push(warning.into());
+        self
+    }
+
+    /// Adds a problem with the project that the plan doesn't fix. It's reported like a warning
+    /// when the plan is executed, but makes a check fail.
+    pub fn problem(&mut self, problem: impl Into<String>) -> &mut Plan {
+        self.problems.push(problem.into());
+        self
+    }
+
+    /// Returns a plan containing only the actions whose changes have not already been made.
+    pub fn outstanding(&self) -> Result<Plan> {
+        let mut outstanding = Plan {
+            license: self.license.clone(),
+            actions: vec![],
+            warnings: self.warnings.clone(),
+            problems: self.problems.clone(),
+        };
+
+        for action in &self.actions {
+            if !action.is_satisfied()? {
+                outstanding.actions.push(action.clone());
+            }
+        }
+
+        Ok(outstanding)
+    }
+
+    /// Performs each action in the plan, calling `on_event` as progress is made.
+    ///
+    /// Returns a journal of the changes that were made.
+    pub fn execute<F>(&self, on_event: F) -> Result<Journal>
+    where
+        F: FnMut(Event),
+    {
+        self.execute_with(&ExecuteOptions::default(), on_event)
+    }
+
+    /// Performs each action in the plan with the given options, calling `on_event` as progress is
+    /// made.
+    ///
+    /// Returns a journal of the changes that were made, which is also saved in
+    /// [`ExecuteOptions::journal_dir`] if it's set. A journal without changes isn't saved, so that
+    /// a run that changes nothing doesn't replace the journal of the previous one.
+    pub fn execute_with<F>(&self, options: &ExecuteOptions, mut on_event: F) -> Result<Journal>
+    where
+        F: FnMut(Event),
+    {
+        for warning in self.warnings.iter().chain(&self.problems) {
+            on_event(Event::Warning(warning));
+        }
+
+        for action in &self.actions {
+            if let Action::WriteFile { path, .. } = action {
+                on_event(Event::FilePlanned(path));
+            }
+        }
+
+        let mut journal = Journal::new();
+        let executed = self.execute_actions(options, &mut journal, on_event);
+
+        // The journal is saved even if an action failed, so that the earlier ones can be reverted.
+        let saved = match &options.journal_dir {
+            Some(dir) if !journal.is_empty() => journal.save(dir),
+            _ => Ok(()),
+        };
+        executed?;
+        saved?;
+
+        Ok(journal)
+    }
+
+    /// Performs each action in the plan, recording the changes in `journal`.
+    fn execute_actions<F>(
+        &self,
+        options: &ExecuteOptions,
+        journal: &mut Journal,
+        mut on_event: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Event),
+    {
+        for action in &self.actions {
+            // Leave files that are already up to date untouched, so that their modification times
+            // don't change and build tools don't see them as dirty.
+            if action.is_satisfied()? {
+                on_event(Event::Unchanged(action.path()));
+                continue;
+            }
+
+            let original = read_if_exists(action.path())?;
+
+            if options.backup && original.is_some() {
+                let backup = backup_path(action.path());
+                fs::copy(action.path(), &backup)
+                    .with_context(|| format!("could not back up {}", action.path().display()))?;
+                on_event(Event::BackedUp {
+                    path: action.path(),
+                    backup: &backup,
+                });
+            }
+
+            let written = match action {
+                Action::WriteFile { path, contents } => {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent).with_context(|| {
+                            format!("could not create directory {}", parent.display())
+                        })?;
+                    }
+                    write_atomic(path, contents)?;
+                    on_event(Event::FileWritten {
+                        path,
+                        created: original.is_none(),
+                        contents,
+                    });
+                    Some(contents.clone())
+                }
+                Action::RemoveFile { path } => {
+                    if original.is_some() {
+                        fs::remove_file(path)
+                            .with_context(|| format!("could not remove {}", path.display()))?;
+                        on_event(Event::FileRemoved(path));
+                    }
+                    None
+                }
+                Action::UpdateManifest { path, field, value } => {
+                    let written = update_manifest(path, field, Some(value))?;
+                    on_event(Event::ManifestUpdated { path, field, value });
+                    Some(written)
+                }
+                Action::RemoveManifestField { path, field } => {
+                    let written = update_manifest(path, field, None)?;
+                    on_event(Event::ManifestFieldRemoved { path, field });
+                    Some(written)
+                }
+            };
+
+            journal.record(action.path(), original, written)?;
+        }
+
+        Ok(())
+    }
+}
+
+trait ItemExt {
+    fn pointer(&self, field: &str) -> Option<&str>;
+
+    fn contains(&self, field: &str) -> bool;
+}
+
+impl ItemExt for Item {
+    /// Looks up the string at the dotted path `field`.
+    fn pointer(&self, field: &str) -> Option<&str> {
+        field
+            .split('.')
+            .try_fold(self, |item, key| item.get(key))
+            .and_then(Item::as_str)
+    }
+
+    /// Returns true if there is any value at the dotted path `field`.
+    fn contains(&self, field: &str) -> bool {
+        field
+            .split('.')
+            .try_fold(self, |item, key| item.get(key))
+            .is_some()
+    }
+}
+
+/// Returns the path that `path` is backed up to, such as `LICENSE.bak` for `LICENSE`.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("could not read {}", path.display())),
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Document> {
+    fs::read_to_string(path)
+        .with_context(|| format!("could not read {}", path.display()))?
+        .parse()
+        .with_context(|| format!("could not parse {}", path.display()))
+}
+
+/// Returns true if the manifest at `path` is JSON, such as `package.json`, rather than TOML.
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn read_json_manifest(path: &Path) -> Result<Value> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("could not parse {}", path.display()))
+}
+
+/// Looks up the value at the dotted path `field` of a JSON manifest.
+fn json_pointer<'a>(manifest: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(manifest, |value, key| value.get(key))
+}
+
+/// Returns the contents of the JSON manifest with the dotted `field` set to `value`, or removed if
+/// `value` is `None`. The order of the fields is kept, but the manifest is reindented with two
+/// spaces, as npm writes it.
+fn updated_json_manifest(
+    path: &Path,
+    contents: &str,
+    field: &str,
+    value: Option<&str>,
+) -> Result<String> {
+    let mut manifest: Value = serde_json::from_str(contents)
+        .with_context(|| format!("could not parse {}", path.display()))?;
+
+    let (parents, key) = match field.rsplit_once('.') {
+        Some((parents, key)) => (Some(parents), key),
+        None => (None, field),
+    };
+
+    let mut object = &mut manifest;
+    for parent in parents.into_iter().flat_map(|parents| parents.split('.')) {
+        object = object
+            .as_object_mut()
+            .with_context(|| format!("{} is not a JSON object", path.display()))?
+            .entry(parent)
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+    let object = object
+        .as_object_mut()
+        .with_context(|| format!("could not set {} in {}", field, path.display()))?;
+    match value {
+        Some(value) => {
+            object.insert(key.to_owned(), value.into());
+        }
+        None => {
+            object.shift_remove(key);
+        }
+    }
+
+    let mut updated = serde_json::to_string_pretty(&manifest)?;
+    if contents.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Returns the contents of the manifest with the dotted `field` set to `value`, or removed if
+/// `value` is `None`.
+fn updated_manifest(
+    path: &Path,
+    contents: &str,
+    field: &str,
+    value: Option<&str>,
+) -> Result<String> {
+    if is_json(path) {
+        return updated_json_manifest(path, contents, field, value);
+    }
+
+    let mut manifest: Document = contents
+        .parse()
+        .with_context(|| format!("could not parse {}", path.display()))?;
+
+    let mut item = manifest.as_item_mut();
+    for key in field.split('.') {
+        item = &mut item[key];
+    }
+    *item = match value {
+        Some(value) => toml_edit::value(value),
+        None => Item::None,
+    };
+
+    Ok(manifest.to_string())
+}
+
+/// Updates the field in the manifest, returning the new contents.
+fn update_manifest(path: &Path, field: &str, value: Option<&str>) -> Result<String> {
+    let original =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let updated = updated_manifest(path, &original, field, value)?;
+
+    write_atomic(path, &updated)?;
+
+    Ok(updated)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`, so that the
+/// file is never left half-written if the process is interrupted or the disk fills up.
+///
+/// The permissions of an existing file are preserved.
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} is not a file", path.display()))?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", process::id()));
+    let temp = path.with_file_name(temp_name);
+
+    let result = (|| {
+        fs::write(&temp, contents)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp, metadata.permissions())?;
+        }
+        fs::rename(&temp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+
+    result.with_context(|| format!("could not write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::{Event, ExecuteOptions, Journal, Plan};
+
+    #[test]
+    fn execute() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&manifest, "[package]\nname = \"foo\"\n")?;
+
+        let mut plan = Plan::new();
+        plan.write_file(&license, "text")
+            .update_manifest(&manifest, "package.license", "MIT")
+            .warn("careful");
+
+        let mut events = vec![];
+        plan.execute(|event| events.push(format!("{:?}", event)))?;
+
+        assert_eq!(fs::read_to_string(&license)?, "text");
+        assert_eq!(
+            fs::read_to_string(&manifest)?,
+            "[package]\nname = \"foo\"\nlicense = \"MIT\"\n"
+        );
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0], format!("{:?}", Event::Warning("careful")));
+
+        assert!(plan.outstanding()?.actions.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn backup() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        fs::write(&license, "old")?;
+
+        let mut plan = Plan::new();
+        plan.write_file(&license, "new")
+            .write_file(dir.path().join("LICENSE-MIT"), "mit");
+        plan.execute_with(ExecuteOptions::new().backup(true), |_| ())?;
+
+        assert_eq!(fs::read_to_string(&license)?, "new");
+        assert_eq!(fs::read_to_string(dir.path().join("LICENSE.bak"))?, "old");
+        assert!(!dir.path().join("LICENSE-MIT.bak").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_dir() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&manifest, "not [toml")?;
+
+        let mut plan = Plan::new();
+        plan.write_file(&license, "new")
+            .update_manifest(&manifest, "package.license", "MIT");
+        assert!(plan
+            .execute_with(ExecuteOptions::new().journal_dir(dir.path()), |_| ())
+            .is_err());
+
+        let journal = Journal::load(dir.path())?.unwrap();
+        assert_eq!(journal.entries.len(), 1);
+        journal.undo(false, |_| ())?;
+        assert!(!license.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_file() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        fs::write(&license, "old")?;
+
+        let mut plan = Plan::new();
+        plan.remove_file(&license);
+        let journal = plan.execute(|_| ())?;
+
+        assert!(!license.exists());
+        assert!(plan.outstanding()?.actions.is_empty());
+
+        journal.undo(false, |_| ())?;
+        assert_eq!(fs::read_to_string(&license)?, "old");
+
+        Ok(())
+    }
+
+    #[test]
+    fn outstanding() -> Result<()> {
+        let dir = tempdir()?;
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&manifest, "[package]\nlicense = \"MIT\"\n")?;
+        fs::write(dir.path().join("LICENSE-MIT"), "mit")?;
+
+        let mut plan = Plan::new();
+        plan.license("MIT OR Apache-2.0")
+            .write_file(dir.path().join("LICENSE-MIT"), "mit")
+            .write_file(dir.path().join("LICENSE-APACHE"), "apache")
+            .update_manifest(&manifest, "package.license", "MIT")
+            .update_manifest(&manifest, "package.license", "MIT OR Apache-2.0");
+
+        let mut expected = Plan::new();
+        expected
+            .license("MIT OR Apache-2.0")
+            .write_file(dir.path().join("LICENSE-APACHE"), "apache")
+            .update_manifest(&manifest, "package.license", "MIT OR Apache-2.0");
+
+        assert_eq!(plan.outstanding()?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_atomic() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        fs::write(&license, "old")?;
+
+        super::write_atomic(&license, "new")?;
+
+        assert_eq!(fs::read_to_string(&license)?, "new");
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_unchanged() -> Result<()> {
+        let dir = tempdir()?;
+        let license = dir.path().join("LICENSE");
+        fs::write(&license, "text")?;
+        let modified = fs::metadata(&license)?.modified()?;
+
+        let mut plan = Plan::new();
+        plan.write_file(&license, "text");
+
+        let mut events = vec![];
+        let journal = plan.execute(|event| events.push(format!("{:?}", event)))?;
+
+        assert!(journal.entries.is_empty());
+        assert_eq!(
+            events.last().unwrap(),
+            &format!("{:?}", Event::Unchanged(&license))
+        );
+        assert_eq!(fs::metadata(&license)?.modified()?, modified);
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_manifest() -> Result<()> {
+        let dir = tempdir()?;
+        let manifest = dir.path().join("package.json");
+        fs::write(
+            &manifest,
+            "{\n  \"name\": \"foo\",\n  \"license\": \"ISC\",\n  \"version\": \"1.0.0\"\n}\n",
+        )?;
+
+        let mut plan = Plan::new();
+        plan.update_manifest(&manifest, "license", "MIT");
+        plan.execute(|_| ())?;
+
+        assert_eq!(
+            fs::read_to_string(&manifest)?,
+            "{\n  \"name\": \"foo\",\n  \"license\": \"MIT\",\n  \"version\": \"1.0.0\"\n}\n"
+        );
+        assert!(plan.outstanding()?.actions.is_empty());
+
+        let mut plan = Plan::new();
+        plan.remove_manifest_field(&manifest, "license");
+        plan.execute(|_| ())?;
+
+        assert_eq!(
+            fs::read_to_string(&manifest)?,
+            "{\n  \"name\": \"foo\",\n  \"version\": \"1.0.0\"\n}\n"
+        );
+
+        Ok(())
+    }
+}
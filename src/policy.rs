@@ -0,0 +1,131 @@
+//! Checking license expressions against an organization's license policy.
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+
+use crate::Expr;
+
+/// Lists of licenses that are allowed or denied, such as "only permissive licenses".
+///
+/// # Example
+///
+/// ```
+/// use apply_license::{Expr, Policy};
+///
+/// let mut policy = Policy::new();
+/// policy.allow("MIT").allow("Apache-2.0");
+///
+/// let evaluation = policy.evaluate(&Expr::parse("MIT AND GPL-3.0")?);
+/// assert!(!evaluation.satisfied);
+/// assert_eq!(evaluation.violations, ["GPL-3.0"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Policy {
+    /// The license identifiers that are allowed. If empty, every license that isn't denied is
+    /// allowed.
+    #[serde(default)]
+    pub allow: BTreeSet<String>,
+
+    /// The license identifiers that are never allowed.
+    #[serde(default)]
+    pub deny: BTreeSet<String>,
+}
+
+/// The result of evaluating a license expression against a [`Policy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Evaluation {
+    /// Whether the expression can be satisfied without using any license that violates the
+    /// policy. For example, `MIT OR GPL-3.0` satisfies a policy denying `GPL-3.0`, because `MIT`
+    /// may be chosen instead.
+    pub satisfied: bool,
+
+    /// The license identifiers in the expression that violate the policy, in the order that they
+    /// appear, even if the expression offers an alternative to them.
+    pub violations: Vec<String>,
+}
+
+impl Policy {
+    /// Creates a policy that allows every license.
+    pub fn new() -> Policy {
+        Policy::default()
+    }
+
+    /// Adds a license to the allow list.
+    pub fn allow(&mut self, license: impl Into<String>) -> &mut Policy {
+        self.allow.insert(license.into());
+        self
+    }
+
+    /// Adds a license to the deny list.
+    pub fn deny(&mut self, license: impl Into<String>) -> &mut Policy {
+        self.deny.insert(license.into());
+        self
+    }
+
+    /// Returns true if the policy allows a single license identifier. Exceptions don't affect
+    /// whether a license is allowed.
+    pub fn allows(&self, license: &str) -> bool {
+        !self.deny.contains(license) && (self.allow.is_empty() || self.allow.contains(license))
+    }
+
+    /// Evaluates whether a license expression satisfies the policy.
+    pub fn evaluate(&self, expr: &Expr) -> Evaluation {
+        let mut violations = vec![];
+        for license in expr.licenses() {
+            if !self.allows(license) && !violations.iter().any(|v| v == license) {
+                violations.push(license.to_owned());
+            }
+        }
+
+        Evaluation {
+            satisfied: self.satisfied(expr),
+            violations,
+        }
+    }
+
+    fn satisfied(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::License(license) | Expr::With { license, .. } => self.allows(license),
+            Expr::And(lhs, rhs) => self.satisfied(lhs) && self.satisfied(rhs),
+            Expr::Or(lhs, rhs) => self.satisfied(lhs) || self.satisfied(rhs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Policy};
+
+    #[test]
+    fn evaluate() {
+        let mut policy = Policy::new();
+        policy.deny("GPL-3.0").deny("AGPL-3.0");
+
+        let evaluation = policy.evaluate(&Expr::parse("MIT OR GPL-3.0").unwrap());
+        assert!(evaluation.satisfied);
+        assert_eq!(evaluation.violations, ["GPL-3.0"]);
+
+        let evaluation = policy.evaluate(&Expr::parse("MIT AND (GPL-3.0 OR AGPL-3.0)").unwrap());
+        assert!(!evaluation.satisfied);
+        assert_eq!(evaluation.violations, ["GPL-3.0", "AGPL-3.0"]);
+
+        let evaluation = policy.evaluate(&Expr::parse("Apache-2.0 WITH LLVM-exception").unwrap());
+        assert!(evaluation.satisfied);
+        assert!(evaluation.violations.is_empty());
+    }
+
+    #[test]
+    fn allow_list() {
+        let mut policy = Policy::new();
+        policy.allow("MIT").allow("Apache-2.0").deny("Apache-2.0");
+
+        assert!(policy.allows("MIT"));
+        assert!(!policy.allows("Apache-2.0"));
+        assert!(!policy.allows("ISC"));
+    }
+}
@@ -0,0 +1,140 @@
+//! The license section of a README.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{parse_spdx, spdx_license, Expr, License, PROPRIETARY};
+
+/// Returns true if the README already has a heading for its license section, such as
+/// `## License`.
+pub fn has_license_section(readme: &str) -> bool {
+    readme.lines().any(|line| {
+        let heading = line.trim_start_matches('#');
+        heading.len() < line.len()
+            && matches!(
+                heading.trim().to_lowercase().as_str(),
+                "license" | "licence" | "licensing" | "licenses"
+            )
+    })
+}
+
+/// Renders a Markdown section for a README describing the license expression and linking to the
+/// license files.
+pub fn readme_section(license_expr: &str) -> Result<String> {
+    let expr = Expr::parse(license_expr)?;
+    let licenses = parse_spdx(license_expr)?;
+
+    let links = license_links(&licenses, Path::new(""));
+
+    let mut section = String::from("## License\n\n");
+    match links.as_slice() {
+        [link] => section.push_str(&format!("This project is licensed under the {}.\n", link)),
+        _ => {
+            // Only describe a choice if every license is an alternative to the others.
+            let mut any_and = false;
+            expr.walk(&mut |expr| any_and |= matches!(expr, Expr::And(..)));
+            let either = matches!(expr, Expr::Or(..)) && !any_and;
+            if either {
+                section.push_str("This project is licensed under either of\n\n");
+            } else {
+                section.push_str(&format!("This project is licensed under `{}`:\n\n", expr));
+            }
+
+            for link in &links {
+                section.push_str(&format!("- {}\n", link));
+            }
+
+            if either {
+                section.push_str("\nat your option.\n");
+            }
+        }
+    }
+
+    Ok(section)
+}
+
+/// Renders a paragraph for the license section of a README saying that the documentation in `dir`
+/// is licensed under `license_expr`, linking to its license files.
+pub fn readme_docs_paragraph(license_expr: &str, dir: &Path) -> Result<String> {
+    Expr::parse(license_expr)?;
+    let licenses = parse_spdx(license_expr)?;
+
+    let dir = dir.to_string_lossy();
+    let dir = dir.trim_end_matches('/');
+    let links = license_links(&licenses, Path::new(dir));
+
+    let licenses = match links.as_slice() {
+        [link] => format!("the {}", link),
+        _ => format!("`{}` ({})", license_expr, links.join(", ")),
+    };
+
+    Ok(format!(
+        "The documentation in [`{dir}/`]({dir}/) is licensed under {}.\n",
+        licenses,
+        dir = dir
+    ))
+}
+
+/// Returns Markdown links to the license files for each license, in `dir`.
+fn license_links(licenses: &[&License], dir: &Path) -> Vec<String> {
+    licenses
+        .iter()
+        .map(|license| {
+            let name = match spdx_license(&license.spdx) {
+                Some(spdx) => spdx.name.as_str(),
+                None if license.spdx == PROPRIETARY => "proprietary license",
+                None => license.spdx.as_str(),
+            };
+            let file = if licenses.len() == 1 {
+                String::from("LICENSE")
+            } else {
+                format!("LICENSE-{}", license.identifier)
+            };
+            format!("[{}]({})", name, dir.join(file).display())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{has_license_section, readme_docs_paragraph, readme_section};
+
+    #[test]
+    fn section() {
+        assert_eq!(
+            readme_section("MIT").unwrap(),
+            "## License\n\nThis project is licensed under the [MIT License](LICENSE).\n"
+        );
+
+        let section = readme_section("MIT OR Apache-2.0").unwrap();
+        assert!(section.contains("either of\n\n- [MIT License](LICENSE-MIT)\n"));
+        assert!(section.ends_with("\nat your option.\n"));
+
+        let section = readme_section("MIT AND Apache-2.0").unwrap();
+        assert!(section.contains("under `MIT AND Apache-2.0`:"));
+    }
+
+    #[test]
+    fn docs_paragraph() {
+        assert_eq!(
+            readme_docs_paragraph("MIT", Path::new("docs/")).unwrap(),
+            "The documentation in [`docs/`](docs/) is licensed under the \
+             [MIT License](docs/LICENSE).\n"
+        );
+        assert!(
+            readme_docs_paragraph("MIT OR Apache-2.0", Path::new("docs"))
+                .unwrap()
+                .contains("under `MIT OR Apache-2.0` ([MIT License](docs/LICENSE-MIT), ")
+        );
+    }
+
+    #[test]
+    fn existing_section() {
+        assert!(has_license_section("# foo\n\n## License\n\nMIT\n"));
+        assert!(has_license_section("### Licensing\n"));
+        assert!(!has_license_section("# foo\n\nLicense: MIT\n"));
+    }
+}
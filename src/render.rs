@@ -0,0 +1,966 @@
+//! Rendering of license templates.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, Local};
+use globset::Glob;
+use handlebars::{handlebars_helper, Handlebars, HelperDef};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::attributions::Attributions;
+use crate::{CommentStyle, Config, Dependency, Exception, License, DEFAULT_COPYRIGHT};
+
+/// Template variables that are always provided by the renderer.
+const RESERVED_VARS: &[&str] = &[
+    "year",
+    "copyright_holders",
+    "authors",
+    "copyright",
+    "copyright_symbol",
+];
+
+/// How the copyright symbol is written in copyright lines, as the `copyright_symbol` variable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyrightSymbol {
+    /// `(c)`
+    #[default]
+    Ascii,
+
+    /// `©`
+    Unicode,
+}
+
+impl CopyrightSymbol {
+    /// The text of the symbol.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CopyrightSymbol::Ascii => "(c)",
+            CopyrightSymbol::Unicode => "©",
+        }
+    }
+}
+
+/// A convention for naming license files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum FileNameStyle {
+    /// `LICENSE` for a single license, or `LICENSE-{id}` for each of several licenses.
+    #[default]
+    Standard,
+
+    /// The names recommended by the GNU project: `COPYING` for the GPL and AGPL, and
+    /// `COPYING.LESSER` for the LGPL, along with the GPL 3.0 in `COPYING` for the LGPL 3.0, which
+    /// builds on it. Other licenses are named as in the standard style.
+    Gnu,
+}
+
+/// Who is named in copyright lines, as the `copyright_holders` variable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum HolderStyle {
+    /// The authors, separated by commas.
+    #[default]
+    Authors,
+
+    /// `The {{project}} Contributors`, using the `project` variable, instead of listing individual
+    /// authors.
+    Contributors,
+}
+
+handlebars_helper!(year_range: |start: u64, end: u64| {
+    if start < end {
+        format!("{}-{}", start, end)
+    } else {
+        end.to_string()
+    }
+});
+
+handlebars_helper!(upper: |s: str| s.to_uppercase());
+
+handlebars_helper!(join: |list: array, separator: str| {
+    list.iter()
+        .map(|item| match item {
+            Value::String(s) => s.clone(),
+            item => item.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+});
+
+/// Renders license templates into the contents of license files.
+///
+/// Templates may use these helpers in addition to the ones built into handlebars:
+///
+/// - `{{year_range start end}}` formats a range of years, such as `2018-2022`, or a single year if
+///   `start` is not before `end`.
+/// - `{{upper s}}` converts a string to uppercase.
+/// - `{{join list separator}}` joins the items of a list, such as `{{join authors " and "}}`.
+///
+/// Rendering fails if a template refers to a variable that isn't defined, so that a typo in a
+/// custom template doesn't silently leave a blank in the license text. See [`Renderer::strict`].
+///
+/// # Example
+///
+/// ```
+/// use apply_license::Renderer;
+///
+/// let licenses = apply_license::parse_spdx("MIT")?;
+/// let files = Renderer::new()
+///     .var("project", "foo")
+///     .render(&licenses, &["John Doe"])?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    registry: Handlebars<'static>,
+    partials: BTreeMap<String, String>,
+    copyright: Option<String>,
+    copyright_symbol: CopyrightSymbol,
+    holder_style: HolderStyle,
+    trim_trailing_whitespace: bool,
+    insert_final_newline: bool,
+    year: Option<i32>,
+    no_year: bool,
+    sort_authors: bool,
+    deterministic: bool,
+    provenance: bool,
+    file_names: BTreeMap<String, PathBuf>,
+    file_name_style: FileNameStyle,
+    vars: BTreeMap<String, Value>,
+    exceptions: Vec<(String, Exception)>,
+    header_templates: Vec<(String, String)>,
+}
+
+impl Default for Renderer {
+    fn default() -> Renderer {
+        let mut registry = Handlebars::new();
+        registry.set_strict_mode(true);
+        // License texts are plain text, not HTML.
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_helper("year_range", Box::new(year_range));
+        registry.register_helper("upper", Box::new(upper));
+        registry.register_helper("join", Box::new(join));
+
+        Renderer {
+            registry,
+            partials: BTreeMap::new(),
+            copyright: None,
+            copyright_symbol: CopyrightSymbol::default(),
+            holder_style: HolderStyle::default(),
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            year: None,
+            no_year: false,
+            sort_authors: false,
+            deterministic: false,
+            provenance: false,
+            file_names: BTreeMap::new(),
+            file_name_style: FileNameStyle::default(),
+            vars: BTreeMap::new(),
+            exceptions: vec![],
+            header_templates: vec![],
+        }
+    }
+}
+
+impl Renderer {
+    /// Creates a new renderer with the default template context.
+    pub fn new() -> Renderer {
+        Renderer::default()
+    }
+
+    /// Applies the rendering settings of a project's configuration, such as its copyright format,
+    /// file names, and variables. Settings that the configuration leaves out are left as they are.
+    ///
+    /// The year isn't fixed for [`Config::deterministic`], so combine it with [`Renderer::year`]
+    /// for reproducible output.
+    pub fn configure(&mut self, config: &Config) -> &mut Renderer {
+        if let Some(format) = &config.copyright {
+            self.copyright(format);
+        }
+
+        if let Some(symbol) = config.copyright_symbol {
+            self.copyright_symbol(symbol);
+        }
+
+        if let Some(style) = config.holder_style {
+            self.holder_style(style);
+        }
+
+        if let Some(style) = config.file_name_style {
+            self.file_name_style(style);
+        }
+
+        self.trim_trailing_whitespace |= config.trim_trailing_whitespace;
+        self.insert_final_newline |= config.insert_final_newline;
+        self.provenance |= config.provenance;
+        self.no_year |= config.no_year;
+        self.sort_authors |= config.sort_authors;
+        self.deterministic |= config.deterministic;
+
+        for (license, name) in &config.file_names {
+            self.file_name(license, name);
+        }
+
+        for (pattern, template) in &config.headers {
+            self.header_template(pattern, template);
+        }
+
+        for (key, value) in &config.vars {
+            self.var(key, value.as_str());
+        }
+
+        self
+    }
+
+    /// Registers a handlebars helper, so templates may use it as `{{name ...}}`.
+    ///
+    /// Registering a helper with the same name as a built-in helper replaces it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apply_license::handlebars::handlebars_helper;
+    /// use apply_license::{License, Renderer};
+    ///
+    /// handlebars_helper!(lower: |s: str| s.to_lowercase());
+    ///
+    /// let license = License::new("LicenseRef-Custom", "CUSTOM", "{{lower \"FOO\"}}");
+    /// let files = Renderer::new()
+    ///     .helper("lower", lower)
+    ///     .render(&[&license], &["John Doe"])?;
+    /// assert_eq!(files[std::path::Path::new("LICENSE")], "foo");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn helper(
+        &mut self,
+        name: &str,
+        helper: impl HelperDef + Send + Sync + 'static,
+    ) -> &mut Renderer {
+        self.registry.register_helper(name, Box::new(helper));
+        self
+    }
+
+    /// Registers a handlebars partial, so templates may include it as `{{> name}}`.
+    ///
+    /// Syntax errors in the partial are reported by [`Renderer::render`].
+    pub fn partial(
+        &mut self,
+        name: impl Into<String>,
+        template: impl Into<String>,
+    ) -> &mut Renderer {
+        self.partials.insert(name.into(), template.into());
+        self
+    }
+
+    /// Adds a variable to the template context, so templates may refer to it as `{{key}}`.
+    ///
+    /// The `year`, `copyright_holders`, `authors`, `copyright`, and `copyright_symbol` variables
+    /// are reserved and may not be overridden.
+    pub fn var(&mut self, key: impl Into<String>, value: impl Into<Value>) -> &mut Renderer {
+        self.vars.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the handlebars template of the copyright line for every license, such as
+    /// `Copyright {{year}} {{copyright_holders}}. All rights reserved.`
+    ///
+    /// By default, each license uses its own [`License::copyright`].
+    pub fn copyright(&mut self, format: impl Into<String>) -> &mut Renderer {
+        self.copyright = Some(format.into());
+        self
+    }
+
+    /// Sets how the copyright symbol is written in copyright lines.
+    pub fn copyright_symbol(&mut self, symbol: CopyrightSymbol) -> &mut Renderer {
+        self.copyright_symbol = symbol;
+        self
+    }
+
+    /// Sets who is named in copyright lines. The [`HolderStyle::Contributors`] style requires the
+    /// `project` variable to be set with [`Renderer::var`].
+    pub fn holder_style(&mut self, style: HolderStyle) -> &mut Renderer {
+        self.holder_style = style;
+        self
+    }
+
+    /// Returns whether copyright lines name the authors, so that there must be some.
+    pub(crate) fn names_authors(&self) -> bool {
+        self.holder_style == HolderStyle::Authors
+    }
+
+    /// Strips whitespace from the end of each line of the rendered files.
+    pub fn trim_trailing_whitespace(&mut self, trim: bool) -> &mut Renderer {
+        self.trim_trailing_whitespace = trim;
+        self
+    }
+
+    /// Ensures that the rendered files end with exactly one newline.
+    pub fn insert_final_newline(&mut self, insert: bool) -> &mut Renderer {
+        self.insert_final_newline = insert;
+        self
+    }
+
+    /// Sets whether rendering fails if a template refers to a variable that isn't defined, which
+    /// is the default. Otherwise, undefined variables are rendered as empty strings.
+    pub fn strict(&mut self, strict: bool) -> &mut Renderer {
+        self.registry.set_strict_mode(strict);
+        self
+    }
+
+    /// Sets the year in copyright lines, instead of the current year.
+    pub fn year(&mut self, year: i32) -> &mut Renderer {
+        self.year = Some(year);
+        self
+    }
+
+    /// Leaves the year out of copyright lines, as in `Copyright (c) John Doe`, so that they don't
+    /// need to be updated every year.
+    ///
+    /// The `year` variable is null instead, so custom copyright formats should only include it
+    /// with `{{#if year}}{{year}} {{/if}}`, as [`DEFAULT_COPYRIGHT`] does.
+    pub fn no_year(&mut self, no_year: bool) -> &mut Renderer {
+        self.no_year = no_year;
+        self
+    }
+
+    /// Sorts the authors alphabetically, ignoring case, instead of naming them in the order that
+    /// they're given in.
+    pub fn sort_authors(&mut self, sort: bool) -> &mut Renderer {
+        self.sort_authors = sort;
+        self
+    }
+
+    /// Makes the rendered files independent of the platform and of the order that the authors are
+    /// given in, by sorting the authors and writing `\n` line endings.
+    ///
+    /// Combine this with [`Renderer::year`] to make the output reproducible, since the year is
+    /// otherwise taken from the clock.
+    pub fn deterministic(&mut self, deterministic: bool) -> &mut Renderer {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Ends each license file with a line saying that it was generated by apply-license, with the
+    /// license's SPDX identifier, so that future maintainers know that it's safe to regenerate. The
+    /// line doesn't change over time, so regenerating an up-to-date file leaves it as it is. This
+    /// is off by default, since it changes the canonical license text.
+    pub fn provenance(&mut self, provenance: bool) -> &mut Renderer {
+        self.provenance = provenance;
+        self
+    }
+
+    /// Sets the name of the file for the license with the SPDX identifier `license`, such as
+    /// `LICENSE.Apache2` for `Apache-2.0`, instead of `LICENSE` or `LICENSE-{id}`.
+    pub fn file_name(
+        &mut self,
+        license: impl Into<String>,
+        name: impl Into<PathBuf>,
+    ) -> &mut Renderer {
+        self.file_names.insert(license.into(), name.into());
+        self
+    }
+
+    /// Sets the convention that license files are named by, unless a name is set with
+    /// [`Renderer::file_name`].
+    pub fn file_name_style(&mut self, style: FileNameStyle) -> &mut Renderer {
+        self.file_name_style = style;
+        self
+    }
+
+    /// Appends the text of an exception to the file for the license with the SPDX identifier
+    /// `license`, such as for `Apache-2.0 WITH LLVM-exception`.
+    ///
+    /// See [`parse_exceptions`](crate::parse_exceptions) to find the exceptions in an expression.
+    pub fn exception(
+        &mut self,
+        license: impl Into<String>,
+        exception: &Exception,
+    ) -> &mut Renderer {
+        self.exceptions.push((license.into(), exception.clone()));
+        self
+    }
+
+    /// Uses a handlebars template for the headers of the source files matching the glob
+    /// `pattern`, instead of the copyright line and `SPDX-License-Identifier` tag, such as a
+    /// shorter header for generated code. See [`Renderer::header_for`].
+    ///
+    /// A pattern without a `/`, such as `*.pb.rs`, is matched against the file name, and any other
+    /// pattern against the whole path. The first matching pattern wins. The template may use the
+    /// rendered `copyright` line and the `license` expression, and each of its lines is commented
+    /// out.
+    pub fn header_template(
+        &mut self,
+        pattern: impl Into<String>,
+        template: impl Into<String>,
+    ) -> &mut Renderer {
+        self.header_templates
+            .push((pattern.into(), template.into()));
+        self
+    }
+
+    /// Given a list of authors and licenses, returns a map from file name to contents.
+    ///
+    /// If only one license file is present, the file name will be `LICENSE`. If two or more
+    /// licenses are present, then each file will be named `LICENSE-{id}` (e.g., `LICENSE-MIT`).
+    /// Either may be overridden with [`Renderer::file_name`] or [`Renderer::file_name_style`].
+    pub fn render<S: Borrow<str>>(
+        &self,
+        licenses: &[&License],
+        authors: &[S],
+    ) -> Result<BTreeMap<PathBuf, String>> {
+        let mut licenses = licenses.to_vec();
+
+        // The LGPL 3.0 is a set of additional permissions on top of the GPL 3.0, so GNU recommends
+        // distributing both.
+        if licenses.iter().any(|l| l.spdx.starts_with("LGPL-3.0"))
+            && !licenses.iter().any(|l| l.spdx.starts_with("GPL-3.0"))
+        {
+            if let Some(gpl) = crate::supported_licenses().find(|l| l.spdx == "GPL-3.0") {
+                licenses.push(gpl);
+            }
+        }
+
+        let mut reg = self.registry.clone();
+
+        for (name, template) in &self.partials {
+            reg.register_partial(name, template)
+                .with_context(|| format!("syntax error in partial {}", name))?;
+        }
+
+        for license in &licenses {
+            reg.register_template_string(&license.spdx, &license.text)
+                .with_context(|| format!("syntax error in template for {}", license.spdx))?;
+        }
+
+        let data = self.context(authors)?;
+
+        let mut files = BTreeMap::new();
+        for license in &licenses {
+            let name = self.file_name_for(license, licenses.len());
+
+            let copyright = self.copyright.as_ref().unwrap_or(&license.copyright);
+            let mut data = data.clone();
+            let copyright = reg
+                .render_template(copyright, &data)
+                .context("could not render copyright line")?;
+            data.insert(String::from("copyright"), copyright.into());
+
+            let mut contents = reg
+                .render(&license.spdx, &data)
+                .with_context(|| format!("could not render template for {}", license.spdx))?;
+
+            for (_, exception) in self.exceptions.iter().filter(|(id, _)| *id == license.spdx) {
+                contents.push('\n');
+                contents.push_str(&exception.text);
+            }
+
+            if self.provenance {
+                contents = self.with_provenance(contents, license);
+            }
+
+            if files.contains_key(&name) {
+                bail!(
+                    "more than one license would be written to {} (set a different file name for \
+                     {})",
+                    name.display(),
+                    license.spdx
+                );
+            }
+            files.insert(name, self.normalize_whitespace(contents));
+        }
+
+        Ok(files)
+    }
+
+    /// Returns the name of the file for a license, out of `count` licenses that are being
+    /// rendered.
+    fn file_name_for(&self, license: &License, count: usize) -> PathBuf {
+        if let Some(name) = self.file_names.get(&license.spdx) {
+            return name.clone();
+        }
+
+        if self.file_name_style == FileNameStyle::Gnu {
+            if license.spdx.starts_with("LGPL-") {
+                return PathBuf::from("COPYING.LESSER");
+            } else if license.spdx.starts_with("GPL-") || license.spdx.starts_with("AGPL-") {
+                return PathBuf::from("COPYING");
+            }
+        }
+
+        if count == 1 {
+            PathBuf::from("LICENSE")
+        } else {
+            PathBuf::from(format!("LICENSE-{}", license.identifier))
+        }
+    }
+
+    /// Renders an attributions page listing the dependencies grouped by the license that they
+    /// declare, such as [`ATTRIBUTIONS_TEMPLATE`](crate::ATTRIBUTIONS_TEMPLATE).
+    ///
+    /// The template's context has a `licenses` list, each with the `license` expression, the
+    /// `dependencies` that declare it, and the distinct license `texts` of those dependencies, as
+    /// well as a `dependencies` list of every dependency. Each dependency has a `name`, `version`,
+    /// `license`, and `license_texts` keyed by file name. Variables added with [`Renderer::var`]
+    /// are available too. Unlike license texts, values are HTML-escaped unless they're inserted
+    /// with triple braces.
+    pub fn attributions(&self, template: &str, dependencies: &[Dependency]) -> Result<String> {
+        let mut data = Map::new();
+        for (key, value) in &self.vars {
+            data.insert(key.clone(), value.clone());
+        }
+
+        let attributions = serde_json::to_value(Attributions::new(dependencies))?;
+        if let Value::Object(attributions) = attributions {
+            data.extend(attributions);
+        }
+
+        let mut registry = self.registry.clone();
+        registry.register_escape_fn(handlebars::html_escape);
+        registry
+            .render_template(template, &data)
+            .context("could not render attributions")
+    }
+
+    /// Renders a license header for a source file, made of the copyright line and an
+    /// `SPDX-License-Identifier` tag for `license_expr`, commented out in the given style.
+    pub fn header<S: Borrow<str>>(
+        &self,
+        license_expr: &str,
+        authors: &[S],
+        style: &CommentStyle,
+    ) -> Result<String> {
+        let lines = [
+            self.copyright_line(authors)?,
+            format!("SPDX-License-Identifier: {}", license_expr),
+        ];
+        Ok(style.comment(&lines))
+    }
+
+    /// Renders a license header for the source file at `path`, using the first template added
+    /// with [`Renderer::header_template`] whose pattern matches it, or the same header as
+    /// [`Renderer::header`] if none do.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use apply_license::Renderer;
+    ///
+    /// let style = apply_license::comment_style(Path::new("foo.pb.rs")).unwrap();
+    /// let header = Renderer::new()
+    ///     .header_template("*.pb.rs", "SPDX-License-Identifier: {{license}}")
+    ///     .header_for(Path::new("src/foo.pb.rs"), "MIT", &["John Doe"], &style)?;
+    /// assert_eq!(header, "// SPDX-License-Identifier: MIT\n");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn header_for<S: Borrow<str>>(
+        &self,
+        path: &Path,
+        license_expr: &str,
+        authors: &[S],
+        style: &CommentStyle,
+    ) -> Result<String> {
+        for (pattern, template) in &self.header_templates {
+            let matcher = Glob::new(pattern)
+                .with_context(|| format!("invalid header pattern '{}'", pattern))?
+                .compile_matcher();
+            let matches = if pattern.contains('/') {
+                matcher.is_match(path)
+            } else {
+                path.file_name().is_some_and(|name| matcher.is_match(name))
+            };
+            if !matches {
+                continue;
+            }
+
+            let mut data = self.context(authors)?;
+            data.insert(
+                String::from("copyright"),
+                self.copyright_line(authors)?.into(),
+            );
+            data.insert(String::from("license"), license_expr.into());
+
+            let header = self
+                .registry
+                .render_template(template, &data)
+                .with_context(|| format!("could not render header for '{}'", pattern))?;
+            let lines: Vec<_> = header.trim_end().lines().collect();
+            return Ok(style.comment(&lines));
+        }
+
+        self.header(license_expr, authors, style)
+    }
+
+    /// Renders the copyright line on its own, such as for a source header or `NOTICE` file, using
+    /// [`DEFAULT_COPYRIGHT`] unless another format was set with [`Renderer::copyright`].
+    pub fn copyright_line<S: Borrow<str>>(&self, authors: &[S]) -> Result<String> {
+        let data = self.context(authors)?;
+        let format = self.copyright.as_deref().unwrap_or(DEFAULT_COPYRIGHT);
+        self.registry
+            .render_template(format, &data)
+            .context("could not render copyright line")
+    }
+
+    /// Appends the provenance marker for `license` to the contents of its file.
+    fn with_provenance(&self, mut contents: String, license: &License) -> String {
+        contents.truncate(contents.trim_end().len());
+        contents.push_str(&format!(
+            "\n\nThis file was generated by apply-license (SPDX-License-Identifier: {}).\n",
+            license.spdx
+        ));
+        contents
+    }
+
+    fn normalize_whitespace(&self, mut contents: String) -> String {
+        if self.deterministic {
+            contents = contents.replace("\r\n", "\n");
+        }
+
+        if self.trim_trailing_whitespace {
+            contents = contents
+                .split('\n')
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        if self.insert_final_newline {
+            contents.truncate(contents.trim_end_matches(['\r', '\n']).len());
+            contents.push('\n');
+        }
+
+        contents
+    }
+
+    fn context<S: Borrow<str>>(&self, authors: &[S]) -> Result<Map<String, Value>> {
+        let mut data = Map::new();
+
+        for (key, value) in &self.vars {
+            if RESERVED_VARS.contains(&key.as_str()) {
+                bail!("template variable '{}' is reserved", key);
+            }
+
+            data.insert(key.clone(), value.clone());
+        }
+
+        // The same author may be given more than once, such as from different sources.
+        let authors = authors.iter().map(Borrow::borrow).collect::<Vec<_>>();
+        let mut authors = crate::dedup_authors(&authors);
+
+        if self.sort_authors || self.deterministic {
+            authors.sort_by(|a, b| {
+                a.to_lowercase()
+                    .cmp(&b.to_lowercase())
+                    .then_with(|| a.cmp(b))
+            });
+        }
+
+        let holders = match self.holder_style {
+            HolderStyle::Authors => authors.join(", "),
+            HolderStyle::Contributors => match self.vars.get("project") {
+                Some(Value::String(project)) => format!("The {} Contributors", project),
+                _ => bail!("the contributors holder style requires the `project` variable"),
+            },
+        };
+
+        let year = if self.no_year {
+            Value::Null
+        } else {
+            self.year.unwrap_or_else(|| Local::now().year()).into()
+        };
+        data.insert(String::from("year"), year);
+        data.insert(String::from("copyright_holders"), holders.into());
+        data.insert(
+            String::from("copyright_symbol"),
+            self.copyright_symbol.as_str().into(),
+        );
+        data.insert(String::from("authors"), authors.into());
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{CopyrightSymbol, Exception, FileNameStyle, HolderStyle, License, Renderer};
+
+    #[test]
+    fn custom_vars() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "{{project}} by {{org}}");
+        let files = Renderer::new()
+            .var("project", "foo")
+            .var("org", "Example Inc.")
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert_eq!(files[Path::new("LICENSE")], "foo by Example Inc.");
+    }
+
+    #[test]
+    fn helpers() {
+        let license = License::new(
+            "LicenseRef-Custom",
+            "CUSTOM",
+            "{{year_range 2018 2020}} {{year_range 2020 2020}} {{upper project}} \
+             {{join authors \" and \"}}",
+        );
+        let files = Renderer::new()
+            .var("project", "foo")
+            .render(&[&license], &["John Doe", "Jane Doe"])
+            .unwrap();
+        assert_eq!(
+            files[Path::new("LICENSE")],
+            "2018-2020 2020 FOO John Doe and Jane Doe"
+        );
+    }
+
+    #[test]
+    fn partials() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "[{{> header}}]");
+        let files = Renderer::new()
+            .partial("header", "Copyright {{copyright_holders}}")
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert_eq!(files[Path::new("LICENSE")], "[Copyright John Doe]");
+
+        assert!(Renderer::new()
+            .partial("header", "{{#if}}")
+            .render(&[&license], &["John Doe"])
+            .is_err());
+    }
+
+    #[test]
+    fn copyright() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "{{copyright}}");
+        let files = Renderer::new().render(&[&license], &["John Doe"]).unwrap();
+        assert!(files[Path::new("LICENSE")].starts_with("Copyright (c) 2"));
+
+        let files = Renderer::new()
+            .copyright("{{copyright_holders}}. All rights reserved.")
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert_eq!(
+            files[Path::new("LICENSE")],
+            "John Doe. All rights reserved."
+        );
+
+        let files = Renderer::new()
+            .copyright_symbol(CopyrightSymbol::Unicode)
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert!(files[Path::new("LICENSE")].starts_with("Copyright © 2"));
+
+        let files = Renderer::new()
+            .year(2020)
+            .render(&[&license], &["AT&T", "Conan O'Brien <co@example.com>"])
+            .unwrap();
+        assert_eq!(
+            files[Path::new("LICENSE")],
+            "Copyright (c) 2020 AT&T, Conan O'Brien <co@example.com>"
+        );
+    }
+
+    #[test]
+    fn whitespace() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "foo  \nbar\t\n\n");
+        let render = |renderer: &Renderer| {
+            let files = renderer.render(&[&license], &["John Doe"]).unwrap();
+            files[Path::new("LICENSE")].clone()
+        };
+
+        assert_eq!(render(&Renderer::new()), "foo  \nbar\t\n\n");
+        assert_eq!(
+            render(Renderer::new().trim_trailing_whitespace(true)),
+            "foo\nbar\n\n"
+        );
+        assert_eq!(
+            render(Renderer::new().insert_final_newline(true)),
+            "foo  \nbar\t\n"
+        );
+        assert_eq!(
+            render(
+                Renderer::new()
+                    .trim_trailing_whitespace(true)
+                    .insert_final_newline(true)
+            ),
+            "foo\nbar\n"
+        );
+    }
+
+    #[test]
+    fn exceptions() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "license\n");
+        let other = License::new("LicenseRef-Other", "OTHER", "other\n");
+        let exception = Exception::new("LicenseRef-Exception", "exception\n");
+        let files = Renderer::new()
+            .exception("LicenseRef-Custom", &exception)
+            .render(&[&license, &other], &["John Doe"])
+            .unwrap();
+        assert_eq!(files[Path::new("LICENSE-CUSTOM")], "license\n\nexception\n");
+        assert_eq!(files[Path::new("LICENSE-OTHER")], "other\n");
+    }
+
+    #[test]
+    fn reserved_vars() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "{{year}}");
+        assert!(Renderer::new()
+            .var("year", 1970)
+            .render(&[&license], &["John Doe"])
+            .is_err());
+    }
+
+    #[test]
+    fn deterministic() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "{{copyright}}\r\n");
+        let files = Renderer::new()
+            .year(2020)
+            .deterministic(true)
+            .render(&[&license], &["Jane Doe", "Example Inc."])
+            .unwrap();
+        assert_eq!(
+            files[Path::new("LICENSE")],
+            "Copyright (c) 2020 Example Inc., Jane Doe\n"
+        );
+    }
+
+    #[test]
+    fn sort_authors() {
+        let mut renderer = Renderer::new();
+        renderer.year(2020);
+        let authors = ["jane doe", "John Doe", "Jane Doe", "Example Inc."];
+        assert_eq!(
+            renderer.copyright_line(&authors).unwrap(),
+            "Copyright (c) 2020 jane doe, John Doe, Example Inc."
+        );
+        assert_eq!(
+            renderer
+                .sort_authors(true)
+                .copyright_line(&authors)
+                .unwrap(),
+            "Copyright (c) 2020 Example Inc., jane doe, John Doe"
+        );
+
+        // Authors are deduplicated by name and email, as with `dedup_authors`.
+        assert_eq!(
+            renderer
+                .copyright_line(&[
+                    "Jane Doe",
+                    "jane doe <jd@example.com>",
+                    "J. D. <jd@example.com>"
+                ])
+                .unwrap(),
+            "Copyright (c) 2020 Jane Doe"
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "{{copyright}} {{compnay}}");
+        let err = Renderer::new()
+            .var("company", "Example Inc.")
+            .render(&[&license], &["John Doe"])
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("\"compnay\" not found"));
+
+        let files = Renderer::new()
+            .strict(false)
+            .copyright("{{copyright_holders}}")
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert_eq!(files[Path::new("LICENSE")], "John Doe ");
+    }
+
+    #[test]
+    fn file_names() {
+        let licenses = crate::parse_spdx("MIT OR Apache-2.0").unwrap();
+        let files = Renderer::new()
+            .file_name("Apache-2.0", "LICENSE.Apache2")
+            .render(&licenses, &["John Doe"])
+            .unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [Path::new("LICENSE-MIT"), Path::new("LICENSE.Apache2")]
+        );
+    }
+
+    #[test]
+    fn gnu_file_names() {
+        let gpl = crate::parse_spdx("GPL-3.0").unwrap();
+        let files = Renderer::new()
+            .file_name_style(FileNameStyle::Gnu)
+            .render(&gpl, &["John Doe"])
+            .unwrap();
+        assert_eq!(files.keys().collect::<Vec<_>>(), [Path::new("COPYING")]);
+
+        let lgpl = License::new("LGPL-3.0-only", "LGPL-3.0", "lesser\n");
+        let files = Renderer::new()
+            .file_name_style(FileNameStyle::Gnu)
+            .render(&[&lgpl], &["John Doe"])
+            .unwrap();
+        assert_eq!(files[Path::new("COPYING.LESSER")], "lesser\n");
+        assert!(files[Path::new("COPYING")].contains("GNU GENERAL PUBLIC LICENSE"));
+
+        let agpl = License::new("AGPL-3.0-only", "AGPL-3.0", "affero\n");
+        assert!(Renderer::new()
+            .file_name_style(FileNameStyle::Gnu)
+            .render(&[gpl[0], &agpl], &["John Doe"])
+            .is_err());
+    }
+
+    #[test]
+    fn provenance() {
+        let license = License::new("LicenseRef-Custom", "CUSTOM", "text\n\n");
+        let files = Renderer::new()
+            .provenance(true)
+            .render(&[&license], &["John Doe"])
+            .unwrap();
+        assert_eq!(
+            files[Path::new("LICENSE")],
+            "text\n\nThis file was generated by apply-license \
+             (SPDX-License-Identifier: LicenseRef-Custom).\n"
+        );
+
+        let files = Renderer::new().render(&[&license], &["John Doe"]).unwrap();
+        assert_eq!(files[Path::new("LICENSE")], "text\n\n");
+    }
+
+    #[test]
+    fn no_year() {
+        let licenses = crate::parse_spdx("MIT OR Apache-2.0").unwrap();
+        let files = Renderer::new()
+            .no_year(true)
+            .render(&licenses, &["John Doe"])
+            .unwrap();
+        assert!(files[Path::new("LICENSE-MIT")].starts_with("Copyright (c) John Doe\n"));
+        assert!(files[Path::new("LICENSE-APACHE")].contains("Copyright John Doe\n"));
+
+        let files = Renderer::new()
+            .year(2020)
+            .render(&licenses, &["John Doe"])
+            .unwrap();
+        assert!(files[Path::new("LICENSE-MIT")].starts_with("Copyright (c) 2020 John Doe\n"));
+    }
+
+    #[test]
+    fn contributors() {
+        let mut renderer = Renderer::new();
+        renderer.holder_style(HolderStyle::Contributors).year(2020);
+        assert!(renderer.copyright_line(&["John Doe"]).is_err());
+
+        renderer.var("project", "foo");
+        assert_eq!(
+            renderer.copyright_line::<&str>(&[]).unwrap(),
+            "Copyright (c) 2020 The foo Contributors"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Machine-readable summaries of what a run did.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{Action, Event, Plan};
+
+/// A summary of the changes that were made to a project, or would be made in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// Whether the changes were only planned, and not performed.
+    pub dry_run: bool,
+
+    /// The license expression that was applied, if any.
+    pub license: Option<String>,
+
+    /// The files that were written or removed.
+    pub files: Vec<FileChange>,
+
+    /// The copies that were made of files before they were modified.
+    pub backups: Vec<PathBuf>,
+
+    /// The manifest fields that were updated.
+    pub manifest_changes: Vec<ManifestChange>,
+
+    /// Any warnings that were emitted.
+    pub warnings: Vec<String>,
+
+    /// Any problems with the project that weren't fixed, which make a check fail.
+    pub problems: Vec<String>,
+}
+
+/// A file that was written or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    /// The path of the file.
+    pub path: PathBuf,
+
+    /// How the file was changed.
+    pub status: FileStatus,
+
+    /// The hex-encoded SHA-256 checksum of the file's new contents, or `None` if the file was
+    /// removed.
+    pub sha256: Option<String>,
+}
+
+/// How a file was changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum FileStatus {
+    /// The file did not exist, and was created.
+    Created,
+
+    /// The file already existed, and was overwritten.
+    Updated,
+
+    /// The file was removed.
+    Removed,
+}
+
+impl FileStatus {
+    fn new(created: bool) -> FileStatus {
+        if created {
+            FileStatus::Created
+        } else {
+            FileStatus::Updated
+        }
+    }
+}
+
+/// Returns the hex-encoded SHA-256 checksum of `contents`.
+fn sha256(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// An update to a manifest field, or its removal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestChange {
+    /// The path to the manifest.
+    pub path: PathBuf,
+
+    /// The dotted path to the field.
+    pub field: String,
+
+    /// The new value of the field, or `None` if the field was removed.
+    pub value: Option<String>,
+}
+
+impl Report {
+    /// Creates an empty report for the execution of a plan, to be filled in with
+    /// [`Report::record`].
+    pub fn new(plan: &Plan) -> Report {
+        Report {
+            license: plan.license.clone(),
+            ..Report::default()
+        }
+    }
+
+    /// Creates a report describing what executing the plan would do, without executing it.
+    pub fn from_plan(plan: &Plan) -> Report {
+        let mut report = Report {
+            dry_run: true,
+            license: plan.license.clone(),
+            warnings: plan.warnings.clone(),
+            problems: plan.problems.clone(),
+            ..Report::default()
+        };
+
+        for action in &plan.actions {
+            match action {
+                Action::WriteFile { path, contents } => report.files.push(FileChange {
+                    path: path.clone(),
+                    status: FileStatus::new(!path.exists()),
+                    sha256: Some(sha256(contents)),
+                }),
+                Action::RemoveFile { path } => report.files.push(FileChange {
+                    path: path.clone(),
+                    status: FileStatus::Removed,
+                    sha256: None,
+                }),
+                Action::UpdateManifest { path, field, value } => {
+                    report.manifest_changes.push(ManifestChange {
+                        path: path.clone(),
+                        field: field.clone(),
+                        value: Some(value.clone()),
+                    })
+                }
+                Action::RemoveManifestField { path, field } => {
+                    report.manifest_changes.push(ManifestChange {
+                        path: path.clone(),
+                        field: field.clone(),
+                        value: None,
+                    })
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Updates the report with an event emitted by [`Plan::execute`].
+    pub fn record(&mut self, event: Event) {
+        match event {
+            Event::FileWritten {
+                path,
+                created,
+                contents,
+            } => self.files.push(FileChange {
+                path: path.to_owned(),
+                status: FileStatus::new(created),
+                sha256: Some(sha256(contents)),
+            }),
+            Event::ManifestUpdated { path, field, value } => {
+                self.manifest_changes.push(ManifestChange {
+                    path: path.to_owned(),
+                    field: field.to_owned(),
+                    value: Some(value.to_owned()),
+                })
+            }
+            Event::ManifestFieldRemoved { path, field } => {
+                self.manifest_changes.push(ManifestChange {
+                    path: path.to_owned(),
+                    field: field.to_owned(),
+                    value: None,
+                })
+            }
+            Event::FileRemoved(path) => self.files.push(FileChange {
+                path: path.to_owned(),
+                status: FileStatus::Removed,
+                sha256: None,
+            }),
+            Event::BackedUp { backup, .. } => self.backups.push(backup.to_owned()),
+            Event::Warning(warning) => self.warnings.push(warning.to_owned()),
+            Event::FilePlanned(_) | Event::Unchanged(_) => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sha256;
+    use crate::{Plan, Report};
+
+    #[test]
+    fn checksum() {
+        assert_eq!(
+            sha256("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn dry_run_matches_execution() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut plan = Plan::new();
+        plan.license("MIT")
+            .write_file(dir.path().join("LICENSE"), "text")
+            .warn("careful");
+
+        let expected = Report::from_plan(&plan);
+
+        let mut report = Report::new(&plan);
+        plan.execute(|event| report.record(event)).unwrap();
+
+        assert_eq!(
+            Report {
+                dry_run: true,
+                ..report.clone()
+            },
+            expected
+        );
+    }
+}
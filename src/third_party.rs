@@ -0,0 +1,299 @@
+//! Collecting the licenses of third-party code, such as a `vendor/` or `third_party/` directory or
+//! a project's dependencies, separately from the project's own license files.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::Plan;
+
+/// The directory, relative to the project, that third-party licenses are collected into.
+pub const THIRD_PARTY_DIR: &str = "LICENSES/third-party";
+
+/// The name of the file that the license texts of a project's dependencies are bundled into.
+pub const THIRD_PARTY_LICENSES: &str = "THIRD-PARTY-LICENSES";
+
+/// The width of the rules separating the dependencies in [`THIRD_PARTY_LICENSES`].
+const RULE_WIDTH: usize = 80;
+
+/// A dependency of the project, and the license texts that it's distributed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Dependency {
+    /// The name of the dependency.
+    pub name: String,
+
+    /// The version of the dependency.
+    pub version: String,
+
+    /// The license expression that the dependency declares, if any.
+    pub license: Option<String>,
+
+    /// The contents of the dependency's license files, keyed by file name.
+    pub license_texts: BTreeMap<String, String>,
+}
+
+impl Dependency {
+    /// Creates a dependency without any license texts.
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        license: Option<String>,
+    ) -> Dependency {
+        Dependency {
+            name: name.into(),
+            version: version.into(),
+            license,
+            license_texts: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the license files at the top of the dependency's source directory, such as its
+    /// directory in cargo's registry cache, along with `license_file` if it's given.
+    pub fn read_license_files(&mut self, dir: &Path, license_file: Option<&Path>) -> Result<()> {
+        let mut files = third_party_license_files(dir)?;
+        if let Some(license_file) = license_file {
+            let license_file = dir.join(license_file);
+            if !files.contains(&license_file) {
+                files.push(license_file);
+            }
+        }
+
+        for file in files {
+            let text = match fs::read_to_string(&file) {
+                Ok(text) => text,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("could not read {}", file.display()))
+                }
+            };
+
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            self.license_texts.insert(name.into_owned(), text);
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the contents of [`THIRD_PARTY_LICENSES`]: the name, version, and declared license of
+/// each dependency, followed by its license texts.
+pub fn third_party_licenses(dependencies: &[Dependency]) -> String {
+    let mut bundle = String::from(
+        "THIRD-PARTY LICENSES\n\n\
+         This file contains the licenses of the third-party dependencies that are distributed \
+         with this\nsoftware.\n",
+    );
+
+    for dependency in dependencies {
+        let license = dependency
+            .license
+            .as_deref()
+            .unwrap_or("no declared license");
+        let _ = write!(
+            bundle,
+            "\n{}\n{} {} ({})\n{}\n",
+            "=".repeat(RULE_WIDTH),
+            dependency.name,
+            dependency.version,
+            license,
+            "=".repeat(RULE_WIDTH)
+        );
+
+        if dependency.license_texts.is_empty() {
+            bundle.push_str("\nNo license file was found.\n");
+        }
+
+        for (name, text) in &dependency.license_texts {
+            let _ = write!(bundle, "\n--- {} ---\n\n{}", name, text.trim_end());
+            bundle.push('\n');
+        }
+    }
+
+    bundle
+}
+
+/// Returns a plan that copies the license files of each package vendored in `vendor_dir` (each of
+/// its subdirectories) to `{output_dir}/{package}/`, such as
+/// `LICENSES/third-party/zlib/LICENSE`.
+///
+/// The plan includes a warning for each package that doesn't seem to have a license file, or if
+/// `vendor_dir` isn't a directory.
+pub fn plan_third_party_licenses(vendor_dir: &Path, output_dir: &Path) -> Result<Plan> {
+    let mut plan = Plan::new();
+
+    if !vendor_dir.is_dir() {
+        plan.warn(format!(
+            "skipping third-party licenses for {}, which is not a directory",
+            vendor_dir.display()
+        ));
+        return Ok(plan);
+    }
+
+    for package in subdirectories(vendor_dir)? {
+        let name = match package.file_name() {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let files = third_party_license_files(&package)?;
+        if files.is_empty() {
+            plan.warn(format!(
+                "could not find a license file in {}",
+                package.display()
+            ));
+        }
+
+        for file in files {
+            let contents = match fs::read_to_string(&file) {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    plan.warn(format!(
+                        "{} is not a text file, so it was not copied",
+                        file.display()
+                    ));
+                    continue;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("could not read {}", file.display()))
+                }
+            };
+
+            let file_name = file.file_name().expect("license files have names");
+            plan.write_file(output_dir.join(name).join(file_name), contents);
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Returns true if the file name looks like a license or notice that should be distributed with
+/// the code, such as `LICENSE-MIT`, `COPYING.txt`, or `NOTICE`.
+fn is_third_party_license_file(name: &str) -> bool {
+    static LICENSE_FILE_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"(?i)^(?:(?:.+[-_.])?licen[cs]e|copying|copyright|notice|unlicense|patents)(?:[-_.].+)?$",
+        )
+        .unwrap()
+    });
+
+    LICENSE_FILE_RE.is_match(name)
+}
+
+/// Finds the license files at the top of a vendored package, sorted by path.
+fn third_party_license_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in fs::read_dir(dir).with_context(|| format!("could not read {}", dir.display()))? {
+        let entry = entry?;
+        if entry.file_type()?.is_file()
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(is_third_party_license_file)
+        {
+            files.push(entry.path());
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the subdirectories of `dir`, sorted by path, skipping hidden ones.
+fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+
+    for entry in fs::read_dir(dir).with_context(|| format!("could not read {}", dir.display()))? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if entry.file_type()?.is_dir() && !hidden {
+            dirs.push(entry.path());
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use super::{
+        is_third_party_license_file, plan_third_party_licenses, third_party_licenses, Dependency,
+    };
+
+    #[test]
+    fn license_file_names() {
+        for name in [
+            "LICENSE",
+            "LICENSE-MIT",
+            "licence.txt",
+            "MIT-LICENSE",
+            "COPYING",
+            "NOTICE",
+        ] {
+            assert!(is_third_party_license_file(name), "{}", name);
+        }
+
+        for name in ["README.md", "lib.rs", "licensed.rs"] {
+            assert!(!is_third_party_license_file(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn plan() -> Result<()> {
+        let dir = tempdir()?;
+        let vendor = dir.path().join("vendor");
+        fs::create_dir_all(vendor.join("zlib"))?;
+        fs::write(vendor.join("zlib/LICENSE"), "zlib license\n")?;
+        fs::write(vendor.join("zlib/zlib.c"), "int main() {}\n")?;
+        fs::create_dir_all(vendor.join("unknown"))?;
+
+        let output = dir.path().join("LICENSES/third-party");
+        let plan = plan_third_party_licenses(&vendor, &output)?;
+
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].path(), output.join("zlib/LICENSE"));
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].contains("unknown"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bundle() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(dir.path().join("LICENSE-MIT"), "MIT license\n")?;
+        fs::write(dir.path().join("COPYING"), "copying\n")?;
+        fs::write(dir.path().join("lib.rs"), "")?;
+
+        let mut foo = Dependency::new("foo", "1.0.0", Some(String::from("MIT")));
+        foo.read_license_files(dir.path(), None)?;
+        assert_eq!(
+            foo.license_texts.keys().collect::<Vec<_>>(),
+            ["COPYING", "LICENSE-MIT"]
+        );
+
+        let bar = Dependency::new("bar", "0.1.0", None);
+
+        let bundle = third_party_licenses(&[foo, bar]);
+        assert!(bundle.contains("foo 1.0.0 (MIT)\n"));
+        assert!(bundle.contains("--- LICENSE-MIT ---\n\nMIT license\n"));
+        assert!(bundle.contains("bar 0.1.0 (no declared license)\n"));
+        assert!(bundle.contains("No license file was found."));
+
+        Ok(())
+    }
+}
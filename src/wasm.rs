@@ -0,0 +1,74 @@
+//! JavaScript bindings, enabled by the `wasm` feature.
+//!
+//! These expose the same templates and license expression parsing as the command-line tools, so
+//! that a web page can generate license files without reimplementing them.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Renderer;
+
+/// A rendered license file.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct LicenseFile {
+    name: String,
+    contents: String,
+}
+
+#[wasm_bindgen]
+impl LicenseFile {
+    /// The name of the file, such as `LICENSE-MIT`.
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// The contents of the file.
+    #[wasm_bindgen(getter)]
+    pub fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+/// Renders the license files for a license expression, including the text of any exceptions.
+#[wasm_bindgen]
+pub fn render(license_expr: &str, authors: Vec<String>) -> Result<Vec<LicenseFile>, JsError> {
+    let licenses = crate::parse_spdx(license_expr).map_err(to_js_error)?;
+
+    let mut renderer = Renderer::new();
+    for (license, exception) in crate::parse_exceptions(license_expr).map_err(to_js_error)? {
+        renderer.exception(license, exception);
+    }
+
+    let files = renderer.render(&licenses, &authors).map_err(to_js_error)?;
+
+    Ok(files
+        .into_iter()
+        .map(|(name, contents)| LicenseFile {
+            name: name.display().to_string(),
+            contents,
+        })
+        .collect())
+}
+
+/// Returns the SPDX identifiers of the licenses in a license expression.
+#[wasm_bindgen(js_name = parseSpdx)]
+pub fn parse_spdx(license_expr: &str) -> Result<Vec<String>, JsError> {
+    let licenses = crate::parse_spdx(license_expr).map_err(to_js_error)?;
+    Ok(licenses
+        .into_iter()
+        .map(|license| license.spdx.clone())
+        .collect())
+}
+
+/// Returns the SPDX identifiers of the licenses that can be rendered.
+#[wasm_bindgen]
+pub fn list() -> Vec<String> {
+    crate::supported_licenses()
+        .map(|license| license.spdx.clone())
+        .collect()
+}
+
+fn to_js_error(e: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", e))
+}
@@ -1,4 +1,7 @@
 use std::fs;
+use std::process::{self, Stdio};
+use std::thread;
+use std::time::Duration;
 
 use anyhow::Result;
 use assert_cmd::Command;
@@ -10,8 +13,8 @@ fn single_license_with_author() -> Result<()> {
     let dir = dir.path();
 
     let mut cmd = Command::cargo_bin("apply-license")?;
-    cmd.current_dir(&dir)
-        .args(&["--author", "John Doe", "--license", "MIT"])
+    cmd.current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT"])
         .assert()
         .success();
 
@@ -32,10 +35,11 @@ fn multiple_license_with_author() -> Result<()> {
     let dir = dir.path();
 
     let mut cmd = Command::cargo_bin("apply-license")?;
-    cmd.current_dir(&dir)
-        .args(&["--author", "John Doe", "--license", "MIT/Apache-2.0"])
+    cmd.current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT/Apache-2.0"])
         .assert()
-        .success();
+        .success()
+        .stdout("Applied MIT/Apache-2.0\nCreated: LICENSE-APACHE, LICENSE-MIT\n");
 
     assert!(!dir.join("LICENSE").exists());
 
@@ -48,3 +52,1341 @@ fn multiple_license_with_author() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn dry_run_json() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "--author",
+            "John Doe",
+            "--license",
+            "MIT",
+            "--dry-run",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["license"], "MIT");
+    assert_eq!(report["files"].as_array().unwrap().len(), 1);
+    assert_eq!(report["files"][0]["path"], "LICENSE");
+    assert_eq!(report["files"][0]["status"], "created");
+    assert!(!dir.join("LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn list_json() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["list", "--json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let licenses: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let mit = licenses.iter().find(|l| l["id"] == "MIT").unwrap();
+    assert_eq!(mit["name"], "MIT License");
+    assert_eq!(mit["osi_approved"], true);
+    assert_eq!(mit["supported"], true);
+    assert_eq!(mit["metadata"]["copyleft"], "none");
+    assert!(licenses.iter().all(|l| l["supported"] == true));
+
+    Ok(())
+}
+
+#[test]
+fn check_exit_codes() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    let args = ["--author", "John Doe", "--license", "MIT"];
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(args)
+        .arg("--check")
+        .assert()
+        .code(1);
+    assert!(!dir.join("LICENSE").exists());
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(args)
+        .assert()
+        .success();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(args)
+        .arg("--check")
+        .assert()
+        .success();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--author", "John Doe", "--license", "foobar", "--check"])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn color() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["--license", "foobar", "--color", "always"])
+        .output()?;
+    assert!(String::from_utf8(output.stderr)?.contains("\x1b[31mError:\x1b[0m"));
+
+    let output = Command::cargo_bin("apply-license")?
+        .args(["--license", "foobar"])
+        .env("NO_COLOR", "1")
+        .output()?;
+    assert!(!String::from_utf8(output.stderr)?.contains('\x1b'));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_diff() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(dir.join("LICENSE"), "Copyright (c) 1970 Jane Doe\n")?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT", "--dry-run"])
+        .args(["--color", "always"])
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Would update \x1b[33mLICENSE\x1b[0m"));
+    assert!(stdout.contains("\x1b[31m-Copyright (c) 1970 Jane Doe\x1b[0m"));
+    assert!(stdout.contains("\x1b[32m+Permission is hereby granted, free of charge, to any person obtaining a copy\x1b[0m"));
+
+    assert_eq!(
+        fs::read_to_string(dir.join("LICENSE"))?,
+        "Copyright (c) 1970 Jane Doe\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn undo() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(dir.join("LICENSE"), "old")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT"])
+        .assert()
+        .success();
+    assert_ne!(fs::read_to_string(dir.join("LICENSE"))?, "old");
+
+    // Running again changes nothing, so the journal of the first run is kept.
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .arg("undo")
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(dir.join("LICENSE"))?, "old");
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .arg("undo")
+        .assert()
+        .code(2);
+
+    // The journal is kept in the project, wherever the tool is run from.
+    fs::create_dir(dir.join("foo"))?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["foo", "--author", "John Doe", "--license", "MIT"])
+        .assert()
+        .success();
+    assert!(dir.join("foo/.apply-license/journal.json").exists());
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["undo", "foo"])
+        .assert()
+        .success();
+    assert!(!dir.join("foo/LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn commit() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    for args in [
+        &["init", "--quiet"][..],
+        &["config", "user.name", "John Doe"],
+        &["config", "user.email", "jd@example.com"],
+    ] {
+        Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--author", "John Doe", "--license", "MIT", "--commit"])
+        .assert()
+        .success();
+
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["log", "--format=%s", "--name-only"])
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Apply MIT license\n\nLICENSE\n"
+    );
+
+    // The commit is made in the project's repository, wherever the tool is run from.
+    let cwd = tempdir()?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(cwd.path())
+        .arg(dir)
+        .args(["--author", "John Doe", "--license", "ISC", "--commit"])
+        .assert()
+        .success();
+
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["log", "-1", "--format=%s", "--name-only"])
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Apply ISC license\n\nLICENSE\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn install_hook() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("git")
+        .current_dir(dir)
+        .args(["init", "--quiet"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .arg("install-hook")
+        .assert()
+        .code(2);
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["install-hook", "--", "-l", "MIT", "-a", "John O'Doe"])
+        .assert()
+        .success();
+
+    let hook = fs::read_to_string(dir.join(".git/hooks/pre-commit"))?;
+    assert!(hook.ends_with("exec apply-license --check '-l' 'MIT' '-a' 'John O'\\''Doe'\n"));
+
+    // A project in a subdirectory is checked by its path from the root of the repository.
+    fs::create_dir(dir.join("sub"))?;
+    let cwd = tempdir()?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(cwd.path())
+        .arg("install-hook")
+        .arg(dir.join("sub"))
+        .args(["--", "-l", "MIT"])
+        .assert()
+        .success();
+
+    let hook = fs::read_to_string(dir.join(".git/hooks/pre-commit"))?;
+    assert!(hook.ends_with("exec apply-license --check 'sub/' '-l' 'MIT'\n"));
+
+    Ok(())
+}
+
+#[test]
+fn github_annotations() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(
+        dir.join("LICENSE"),
+        "MIT License\n\nCopyright (c) 1999 John Doe\n",
+    )?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "-a", "John Doe", "-l", "MIT", "--check", "--format", "github",
+        ])
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "::error file=LICENSE,line=1::LICENSE is out of date\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn single_to_multiple_licenses() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(dir.join("LICENSE-THIRD-PARTY"), "vendored")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT OR Apache-2.0"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Applied MIT OR Apache-2.0\nCreated: LICENSE-APACHE, LICENSE-MIT\nRemoved: LICENSE\n"
+    );
+    assert!(!dir.join("LICENSE").exists());
+    assert!(dir.join("LICENSE-THIRD-PARTY").exists());
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+
+    assert!(dir.join("LICENSE").exists());
+    assert!(!dir.join("LICENSE-MIT").exists());
+    assert!(!dir.join("LICENSE-APACHE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn migrate() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(dir.join("license.txt"), "custom terms")?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--dry-run"])
+        .output()?;
+    assert!(String::from_utf8(output.stderr)?.contains("license.txt looks like a license file"));
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .arg("migrate")
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Created: LICENSE\nRemoved: license.txt\n"
+    );
+    assert_eq!(fs::read_to_string(dir.join("LICENSE"))?, "custom terms");
+
+    Ok(())
+}
+
+#[test]
+fn path_licenses() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::create_dir(dir.join("docs"))?;
+    fs::write(
+        dir.join(".apply-license.toml"),
+        "[paths]\n\"docs/\" = \"Apache-2.0\"\n\"missing/\" = \"MIT\"\n",
+    )?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("missing/ is configured"));
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("Permission is hereby granted"));
+    assert!(fs::read_to_string(dir.join("docs/LICENSE"))?.contains("Apache License"));
+
+    Ok(())
+}
+
+#[test]
+fn license_exception() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "Apache-2.0 WITH LLVM-exception"])
+        .assert()
+        .success();
+
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.contains("Apache License"));
+    assert!(license.contains("---- LLVM Exceptions to the Apache 2.0 License ----"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT WITH foobar"])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn no_license_without_terminal() -> Result<()> {
+    let dir = tempdir()?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir.path())
+        .args(["-a", "John Doe"])
+        .write_stdin("")
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)?.contains("no license specified"));
+
+    Ok(())
+}
+
+#[test]
+fn at_root() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::create_dir_all(dir.join(".git"))?;
+    fs::create_dir_all(dir.join("crates/foo"))?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir.join("crates/foo"))
+        .args(["-a", "John Doe", "-l", "MIT", "--at-root"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Applied MIT\nCreated: ../../LICENSE\n"
+    );
+    assert!(dir.join("LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn detect() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "Apache-2.0"])
+        .assert()
+        .success();
+    fs::rename(dir.join("LICENSE"), dir.join("COPYING"))?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["detect", "COPYING"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)?.starts_with("Apache-2.0 ("));
+
+    Ok(())
+}
+
+#[test]
+fn diff() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["diff", "MIT", "Apache-2.0"])
+        .output()?;
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.starts_with("--- MIT\n+++ Apache-2.0\n"));
+    assert!(stdout.contains("-Permission is hereby granted"));
+    assert!(stdout.contains("+Licensed under the Apache License"));
+
+    Command::cargo_bin("apply-license")?
+        .args(["diff", "MIT OR Apache-2.0", "MIT"])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn copyright_format() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(
+        dir.join(".apply-license.toml"),
+        "copyright = \"Copyright {{copyright_holders}}. All rights reserved.\"\n",
+    )?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.contains("Copyright John Doe. All rights reserved."));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .args(["--copyright-format", "(c) {{copyright_holders}}"])
+        .assert()
+        .success();
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.starts_with("(c) John Doe\n"));
+
+    Ok(())
+}
+
+#[test]
+fn copyright_symbol() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--copyright-symbol",
+            "unicode",
+        ])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.starts_with("Copyright © "));
+
+    Ok(())
+}
+
+#[test]
+fn headers() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(dir.join("main.rs"), "fn main() {}\n")?;
+    fs::write(dir.join("build.sh"), "#!/bin/sh\necho\n")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["headers", "-a", "John Doe", "-l", "MIT", "--check"])
+        .assert()
+        .code(1);
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["headers", "-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+
+    let main = fs::read_to_string(dir.join("main.rs"))?;
+    assert!(main.starts_with("// Copyright (c) "));
+    assert!(main.ends_with("John Doe\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n"));
+    let script = fs::read_to_string(dir.join("build.sh"))?;
+    assert!(script.starts_with("#!/bin/sh\n# Copyright (c) "));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["headers", "-a", "John Doe", "-l", "MIT", "--check"])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn headers_watch() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    let mut child = process::Command::new(assert_cmd::cargo::cargo_bin("apply-license"))
+        .current_dir(dir)
+        .args(["headers", "-a", "John Doe", "-l", "MIT", "--watch"])
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    // Give the watcher time to start.
+    thread::sleep(Duration::from_secs(1));
+    fs::write(dir.join("main.rs"), "fn main() {}\n")?;
+
+    let mut contents = String::new();
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(100));
+        contents = fs::read_to_string(dir.join("main.rs"))?;
+        if contents.contains("SPDX-License-Identifier") {
+            break;
+        }
+    }
+
+    child.kill()?;
+    child.wait()?;
+    assert!(contents.contains("// SPDX-License-Identifier: MIT\n\nfn main() {}\n"));
+
+    Ok(())
+}
+
+#[test]
+fn recursive() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    for project in ["foo", "bar", "nested/baz", "nested/baz/sub"] {
+        fs::create_dir_all(dir.join(project))?;
+    }
+    fs::write(dir.join("foo/Cargo.toml"), "")?;
+    fs::write(dir.join("bar/package.json"), "{}")?;
+    fs::create_dir(dir.join("nested/baz/.git"))?;
+    fs::write(dir.join("nested/baz/sub/Cargo.toml"), "")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--recursive", "."])
+        .assert()
+        .success();
+
+    assert!(dir.join("foo/LICENSE").exists());
+    assert!(dir.join("bar/LICENSE").exists());
+    assert!(dir.join("nested/baz/LICENSE").exists());
+    assert!(!dir.join("nested/baz/sub/LICENSE").exists());
+    assert!(!dir.join("LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn header_for() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["header-for", "main.py", "-a", "John Doe", "-l", "MIT"])
+        .output()?;
+    assert!(output.status.success());
+    let header = String::from_utf8(output.stdout)?;
+    assert!(header.starts_with("# Copyright (c) "));
+    assert!(header.ends_with("John Doe\n# SPDX-License-Identifier: MIT\n"));
+
+    Command::cargo_bin("apply-license")?
+        .args(["header-for", "README", "-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn info() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["info", "GPL-3.0"])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Copyleft: strong"));
+    assert!(stdout.contains("- Same license"));
+    assert!(stdout.contains("https://spdx.org/licenses/GPL-3.0.html"));
+
+    let output = Command::cargo_bin("apply-license")?
+        .args(["info", "GPL-3.0-or-later"])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("https://spdx.org/licenses/GPL-3.0-or-later.html"));
+    assert!(stdout.contains("https://choosealicense.com/licenses/gpl-3.0/"));
+
+    Command::cargo_bin("apply-license")?
+        .args(["info", "foobar"])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn init() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+    fs::write(dir.join("README.md"), "# foo\n")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["init", "-a", "John Doe", "-l", "MIT OR Apache-2.0", "--yes"])
+        .assert()
+        .success();
+
+    assert!(dir.join("LICENSE-MIT").exists());
+    assert!(dir.join("LICENSE-APACHE").exists());
+    assert!(fs::read_to_string(dir.join("Cargo.toml"))?.contains("license = \"MIT OR Apache-2.0\""));
+    assert!(fs::read_to_string(dir.join("src/main.rs"))?
+        .contains("// SPDX-License-Identifier: MIT OR Apache-2.0\n"));
+    assert!(fs::read_to_string(dir.join("README.md"))?
+        .starts_with("# foo\n\n## License\n\nThis project is licensed under either of"));
+    assert!(fs::read_to_string(dir.join("NOTICE"))?.starts_with("foo\nCopyright (c) "));
+
+    Ok(())
+}
+
+#[test]
+fn list_exceptions() -> Result<()> {
+    let output = Command::cargo_bin("apply-license")?
+        .args(["list-exceptions"])
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let llvm = stdout
+        .lines()
+        .find(|line| line.starts_with("LLVM-exception "))
+        .unwrap();
+    assert!(llvm.ends_with("usually with Apache-2.0"));
+
+    Ok(())
+}
+
+#[test]
+fn project_path() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::create_dir(dir.join("project"))?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["project", "-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+
+    assert!(dir.join("project/LICENSE").exists());
+    assert!(!dir.join("LICENSE").exists());
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["missing", "-a", "John Doe", "-l", "MIT"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn third_party() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::create_dir_all(dir.join("vendor/zlib"))?;
+    fs::write(dir.join("vendor/zlib/LICENSE"), "zlib license\n")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--third-party", "vendor"])
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("John Doe"));
+    assert_eq!(
+        fs::read_to_string(dir.join("LICENSES/third-party/zlib/LICENSE"))?,
+        "zlib license\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn thirdparty() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    for (name, manifest) in [
+        (
+            "app",
+            "[dependencies]\ndep = { path = \"../dep\" }\n\n\
+             [dev-dependencies]\ndevdep = { path = \"../devdep\" }\n",
+        ),
+        ("dep", "license = \"MIT\"\n"),
+        ("devdep", "license = \"MIT\"\n"),
+    ] {
+        fs::create_dir_all(dir.join(name).join("src"))?;
+        fs::write(dir.join(name).join("src/lib.rs"), "")?;
+        fs::write(
+            dir.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\n{}",
+                name, manifest
+            ),
+        )?;
+    }
+    fs::write(dir.join("dep/LICENSE"), "dep license\n")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir.join("app"))
+        .args(["thirdparty", "--offline"])
+        .assert()
+        .success();
+
+    let bundle = fs::read_to_string(dir.join("app/THIRD-PARTY-LICENSES"))?;
+    assert!(bundle.contains("dep 0.1.0 (MIT)"));
+    assert!(bundle.contains("dep license"));
+    assert!(!bundle.contains("devdep"));
+    assert!(!bundle.contains("app 0.1.0"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir.join("app"))
+        .args(["attributions", "--offline"])
+        .assert()
+        .success();
+    let page = fs::read_to_string(dir.join("app/ATTRIBUTIONS.md"))?;
+    assert!(page.contains("## MIT\n\n- dep 0.1.0\n"));
+
+    fs::write(
+        dir.join("credits.hbs"),
+        "{{#each dependencies}}{{name}}: {{license}}\n{{/each}}",
+    )?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir.join("app"))
+        .args([
+            "attributions",
+            "--offline",
+            "--template",
+            "../credits.hbs",
+            "--output-file",
+            "credits.html",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.join("app/credits.html"))?,
+        "dep: MIT\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn apply_plan() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--dry-run",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert!(!dir.join("LICENSE").exists());
+    fs::write(dir.join("plan.json"), output.stdout)?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["apply", "--plan", "plan.json"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("John Doe"));
+
+    // Only the actions are required in a hand-written plan.
+    fs::write(
+        dir.join("notice.json"),
+        r#"{ "actions": [{ "action": "write-file", "path": "NOTICE", "contents": "foo\n" }] }"#,
+    )?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["apply", "--plan", "notice.json"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(dir.join("NOTICE"))?, "foo\n");
+
+    fs::write(dir.join("invalid.json"), "{}")?;
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["apply", "--plan", "invalid.json"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn deterministic() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("SOURCE_DATE_EPOCH", "1577836800")
+        .args(["-a", "Jane Doe", "-a", "Example Inc.", "-l", "MIT"])
+        .arg("--deterministic")
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?
+        .starts_with("Copyright (c) 2020 Example Inc., Jane Doe\n"));
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env_remove("SOURCE_DATE_EPOCH")
+        .args(["-a", "Jane Doe", "-l", "MIT", "--deterministic"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    // Otherwise, the year is taken from the last commit of the project, wherever the tool is run
+    // from.
+    Command::new("git")
+        .current_dir(dir)
+        .args(["init", "--quiet"])
+        .assert()
+        .success();
+    Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=Jane Doe",
+            "-c",
+            "user.email=jd@example.com",
+        ])
+        .args(["commit", "--quiet", "--allow-empty", "-m", "Initial commit"])
+        .env("GIT_COMMITTER_DATE", "2015-06-01T00:00:00Z")
+        .assert()
+        .success();
+
+    let cwd = tempdir()?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(cwd.path())
+        .env_remove("SOURCE_DATE_EPOCH")
+        .arg(dir)
+        .args(["-a", "Jane Doe", "-l", "MIT", "--deterministic"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.starts_with("Copyright (c) 2015 Jane Doe\n"));
+
+    Ok(())
+}
+
+#[test]
+fn template_vars() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .args(["--var", "company=Example Inc."])
+        .args(["--copyright-format", "Copyright {{company}}"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.starts_with("Copyright Example Inc.\n"));
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--var", "year=1999"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--var", "company"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn file_names() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(
+        dir.join(".apply-license.toml"),
+        "[file_names]\n\"Apache-2.0\" = \"LICENSE.Apache2\"\n",
+    )?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT OR Apache-2.0"])
+        .assert()
+        .success();
+
+    assert!(dir.join("LICENSE-MIT").exists());
+    assert!(dir.join("LICENSE.Apache2").exists());
+    assert!(!dir.join("LICENSE-APACHE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn gnu_file_names() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "GPL-3.0",
+            "--file-name-style",
+            "gnu",
+        ])
+        .assert()
+        .success();
+
+    assert!(dir.join("COPYING").exists());
+    assert!(!dir.join("LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn checksums() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--dry-run",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let sha256 = report["files"][0]["sha256"].as_str().unwrap().to_owned();
+    assert_eq!(sha256.len(), 64);
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT", "--checksums"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)?.contains(&format!("{}  LICENSE\n", sha256)));
+
+    Ok(())
+}
+
+#[test]
+fn provenance() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("SOURCE_DATE_EPOCH", "1577836800")
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--deterministic",
+            "--provenance",
+        ])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.ends_with(
+        "\n\nThis file was generated by apply-license (SPDX-License-Identifier: MIT).\n"
+    ));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("SOURCE_DATE_EPOCH", "1577836800")
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--deterministic",
+            "--provenance",
+            "--check",
+        ])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn default_authors() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    let config_dir = tempdir()?;
+    let config_dir = config_dir.path();
+
+    // Without any authors, and without a terminal to ask on, there is no one to name.
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", config_dir)
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .args(["-l", "MIT"])
+        .assert()
+        .code(2);
+
+    fs::create_dir(config_dir.join("apply-license"))?;
+    fs::write(
+        config_dir.join("apply-license/config.toml"),
+        "authors = [\"Jane Doe\"]\n",
+    )?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", config_dir)
+        .args(["-l", "MIT"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with(" Jane Doe"));
+
+    Ok(())
+}
+
+#[test]
+fn license_from_stdin_or_env() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "--license", "-"])
+        .write_stdin("MIT\n")
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("Permission is hereby granted"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .env("APPLY_LICENSE_EXPR", "Apache-2.0")
+        .args(["-a", "John Doe"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("Apache License"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "--license", "-"])
+        .write_stdin("")
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn docs_license() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::create_dir(dir.join("docs"))?;
+    fs::write(dir.join("README.md"), "# foo\n")?;
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["init", "-a", "John Doe", "-l", "Apache-2.0"])
+        .args(["--docs-license", "CC-BY-4.0"])
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("Apache License"));
+    assert!(
+        fs::read_to_string(dir.join("docs/LICENSE"))?.starts_with("Attribution 4.0 International")
+    );
+    assert!(fs::read_to_string(dir.join("README.md"))?.ends_with(
+        "\n\nThe documentation in [`docs/`](docs/) is licensed under the \
+         [Creative Commons Attribution 4.0 International](docs/LICENSE).\n"
+    ));
+
+    // The expression is checked even if there's no documentation to apply it to.
+    let dir = tempdir()?;
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir.path())
+        .args([
+            "-a",
+            "John Doe",
+            "-l",
+            "MIT",
+            "--docs-license",
+            "NOT-A-LICENSE",
+        ])
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn package_manifests() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+    fs::write(
+        dir.join("package.json"),
+        "{\n  \"name\": \"foo\",\n  \"version\": \"1.0.0\"\n}\n",
+    )?;
+    fs::write(
+        dir.join("pyproject.toml"),
+        "[project]\nname = \"foo\"\nlicense = { text = \"ISC\" }\n",
+    )?;
+
+    let output = Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-l", "MIT", "-a", "John Doe"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("package.json: set license = \"MIT\""));
+    assert!(!fs::read_to_string(dir.join("package.json"))?.contains("license"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-l", "MIT", "-a", "John Doe", "--yes"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.join("package.json"))?,
+        "{\n  \"name\": \"foo\",\n  \"version\": \"1.0.0\",\n  \"license\": \"MIT\"\n}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("pyproject.toml"))?,
+        "[project]\nname = \"foo\"\nlicense = \"MIT\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn authors_from_git() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(dir.join(".mailmap"), "Jane Doe <jane@example.com>\n")?;
+    for args in [
+        &["init", "--quiet"][..],
+        &["config", "user.name", "J. Doe"],
+        &["config", "user.email", "jane@example.com"],
+        &["commit", "--quiet", "--allow-empty", "-m", "one"],
+        &["commit", "--quiet", "--allow-empty", "-m", "two"],
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "three",
+            "--author",
+            "John Roe <john@example.com>",
+        ],
+    ] {
+        Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--authors-from-git", "--license", "MIT"])
+        .assert()
+        .success();
+
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.contains(" Jane Doe, John Roe\n"));
+    assert!(!license.contains("J. Doe"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args([
+            "--authors-from-git",
+            "--min-commits",
+            "50%",
+            "--license",
+            "MIT",
+        ])
+        .assert()
+        .success();
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.contains(" Jane Doe\n"));
+    assert!(!license.contains("John Roe"));
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["--min-commits", "2", "--license", "MIT"])
+        .assert()
+        .code(2);
+
+    // A project in a subdirectory only names the authors of its own commits.
+    fs::create_dir(dir.join("sub"))?;
+    fs::write(dir.join("sub/README"), "sub")?;
+    for args in [
+        &["add", "sub"][..],
+        &[
+            "commit",
+            "--quiet",
+            "-m",
+            "four",
+            "--author",
+            "Jim Poe <jim@example.com>",
+        ],
+    ] {
+        Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["sub", "--authors-from-git", "--license", "MIT"])
+        .assert()
+        .success();
+    let license = fs::read_to_string(dir.join("sub/LICENSE"))?;
+    assert!(license.contains(" Jim Poe\n"));
+    assert!(!license.contains("Jane Doe"));
+
+    Ok(())
+}
@@ -12,7 +12,7 @@ fn cargo_project_with_author() -> Result<()> {
 
     Command::new("cargo")
         .current_dir(dir)
-        .args(&["init", "--name", "foo"])
+        .args(["init", "--name", "foo"])
         .assert()
         .success();
 
@@ -25,9 +25,17 @@ fn cargo_project_with_author() -> Result<()> {
 
     Command::cargo_bin("cargo-apply-license")?
         .current_dir(dir)
-        .args(&["apply-license"])
+        .args(["apply-license"])
+        .assert()
+        .code(2);
+    assert!(!dir.join("LICENSE-MIT").exists());
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--default-license", "--yes"])
         .assert()
         .success();
+    assert!(dir.join("LICENSE-MIT").exists());
 
     Ok(())
 }
@@ -39,7 +47,7 @@ fn cargo_project_explicit_license() -> Result<()> {
 
     Command::new("cargo")
         .current_dir(dir)
-        .args(&["init", "--name", "foo"])
+        .args(["init", "--name", "foo"])
         .assert()
         .success();
 
@@ -52,7 +60,7 @@ fn cargo_project_explicit_license() -> Result<()> {
 
     Command::cargo_bin("cargo-apply-license")?
         .current_dir(dir)
-        .args(&["apply-license", "--license", "MIT"])
+        .args(["apply-license", "--license", "MIT", "--yes"])
         .assert()
         .success();
 
@@ -61,5 +69,651 @@ fn cargo_project_explicit_license() -> Result<()> {
     assert!(license.exists());
     assert!(fs::read_to_string(license)?.contains("John Doe"));
 
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(document["package"]["license"].as_str(), Some("MIT"));
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_manifest_requires_confirmation() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let cargo_toml_contents = fs::read_to_string(dir.join("Cargo.toml"))?;
+
+    let mut document = cargo_toml_contents.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "MIT"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("Cargo.toml: set package.license = \"MIT\""));
+
+    assert!(dir.join("LICENSE").exists());
+
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert!(document["package"].get("license").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_publish_warnings() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    document["package"]["license"] = toml_edit::value("MIT/Apache-2.0");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--dry-run"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains(
+        "crates.io has deprecated `/` in license expressions, use \"MIT OR Apache-2.0\""
+    ));
+
+    // The manifest keeps an invalid license if it isn't updated.
+    document["package"]["license"] = toml_edit::value("MIT-ish");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "--no-manifest-update",
+            "--dry-run",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?
+        .contains("license = \"MIT-ish\" is not a valid SPDX license expression"));
+
+    // Or no license at all.
+    document["package"]
+        .as_table_mut()
+        .unwrap()
+        .remove("license");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "--no-manifest-update",
+            "--dry-run",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?
+        .contains("neither `license` nor `license-file` is set in the manifest"));
+
+    // Keeping `license-file` alongside a new `license` is redundant.
+    fs::write(dir.join("COPYING"), "Custom license")?;
+    document["package"]["license-file"] = toml_edit::value("COPYING");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "--license-file",
+            "keep",
+            "--dry-run",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?
+        .contains("both `license` and `license-file` are set in the manifest"));
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_license_precedence() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    document["package"]["license"] = toml_edit::value("MIT");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "Apache-2.0", "--yes"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("ignoring --license Apache-2.0"));
+
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("Permission is hereby granted"));
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(document["package"]["license"].as_str(), Some("MIT"));
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_offline() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "--offline",
+            "--dry-run",
+        ])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_manifest_path() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo", "foo"])
+        .assert()
+        .success();
+
+    let manifest = dir.join("foo/Cargo.toml");
+    let mut document = fs::read_to_string(&manifest)?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    document["package"]["license"] = toml_edit::value("MIT");
+    fs::write(&manifest, document.to_string())?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--manifest-path", "foo/Cargo.toml"])
+        .assert()
+        .success();
+    assert!(dir.join("foo/LICENSE").exists());
+    assert!(!dir.join("LICENSE").exists());
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--manifest-path",
+            "foo/Cargo.toml",
+            "--output-dir",
+            ".",
+        ])
+        .assert()
+        .success();
+    assert!(dir.join("LICENSE").exists());
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_proprietary() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["Example Inc."]));
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "proprietary", "--yes"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("set `publish = false`"));
+
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("All rights reserved."));
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(
+        document["package"]["license"].as_str(),
+        Some("LicenseRef-Proprietary")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_license_file() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("apply-license")?
+        .current_dir(dir)
+        .args(["-a", "John Doe", "-l", "MIT"])
+        .assert()
+        .success();
+    fs::rename(dir.join("LICENSE"), dir.join("COPYING"))?;
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    document["package"]["license-file"] = toml_edit::value("COPYING");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license-file", "keep"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(dir.join("COPYING").exists());
+    assert!(!dir.join("LICENSE").exists());
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "--license-file",
+            "keep",
+            "--yes",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert!(dir.join("COPYING").exists());
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(
+        document["package"]["license-file"].as_str(),
+        Some("COPYING")
+    );
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license-file", "convert", "--yes"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)?.contains("Removed package.license-file"));
+
+    assert!(!dir.join("COPYING").exists());
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("John Doe"));
+    let document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(document["package"]["license"].as_str(), Some("MIT"));
+    assert!(document["package"].get("license-file").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn workspace() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"core\", \"plugin\", \"cli\"]\n\n\
+         [workspace.package]\nlicense = \"Apache-2.0\"\n",
+    )?;
+    for (member, license) in [
+        ("core", "license = \"MIT\""),
+        ("plugin", "license = \"GPL-3.0\""),
+        ("cli", "license.workspace = true"),
+    ] {
+        fs::create_dir_all(dir.join(member).join("src"))?;
+        fs::write(dir.join(member).join("src/lib.rs"), "")?;
+        fs::write(
+            dir.join(member).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nauthors = [\"John Doe\"]\n{}\n",
+                member, license
+            ),
+        )?;
+    }
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--workspace", "--yes"])
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(dir.join("core/LICENSE"))?.contains("Permission is hereby granted"));
+    assert!(fs::read_to_string(dir.join("plugin/LICENSE"))?.contains("GNU GENERAL PUBLIC LICENSE"));
+    assert!(fs::read_to_string(dir.join("cli/LICENSE"))?.contains("Apache License"));
+    assert!(!dir.join("LICENSE").exists());
+    assert!(fs::read_to_string(dir.join("cli/Cargo.toml"))?.contains("license.workspace = true"));
+
+    Ok(())
+}
+
+#[test]
+fn cargo_project_path() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["new", "--name", "foo", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("foo/Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    fs::write(dir.join("foo/Cargo.toml"), document.to_string())?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "foo", "--license", "MIT", "--yes"])
+        .assert()
+        .success();
+
+    assert!(dir.join("foo/LICENSE").exists());
+    let document = fs::read_to_string(dir.join("foo/Cargo.toml"))?.parse::<Document>()?;
+    assert_eq!(document["package"]["license"].as_str(), Some("MIT"));
+
+    Ok(())
+}
+
+#[test]
+fn workspace_exclude_member() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"core\", \"examples/demo\", \"core-fuzz\"]\n",
+    )?;
+    for member in ["core", "examples/demo", "core-fuzz"] {
+        let name = member.rsplit('/').next().unwrap();
+        fs::create_dir_all(dir.join(member).join("src"))?;
+        fs::write(dir.join(member).join("src/lib.rs"), "")?;
+        fs::write(
+            dir.join(member).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nauthors = [\"John Doe\"]\n",
+                name
+            ),
+        )?;
+    }
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--workspace",
+            "--license",
+            "MIT",
+            "--exclude-member",
+            "examples/*",
+            "--exclude-member",
+            "*-fuzz",
+            "--yes",
+        ])
+        .assert()
+        .success();
+
+    assert!(dir.join("core/LICENSE").exists());
+    assert!(!dir.join("examples/demo/LICENSE").exists());
+    assert!(!dir.join("core-fuzz/LICENSE").exists());
+    assert!(!fs::read_to_string(dir.join("core-fuzz/Cargo.toml"))?.contains("license"));
+
+    Ok(())
+}
+
+#[test]
+fn contributors_holder_style() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "MIT", "--yes"])
+        .args(["--holder-style", "contributors"])
+        .assert()
+        .success();
+
+    let license = fs::read_to_string(dir.join("LICENSE"))?;
+    assert!(license.starts_with("Copyright (c) "));
+    assert!(license
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with(" The foo Contributors"));
+
+    Ok(())
+}
+
+#[test]
+fn workspace_authors() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"core\"]\n\n\
+         [workspace.package]\nauthors = [\"Jane Doe <jane@example.com>\"]\n",
+    )?;
+    fs::create_dir_all(dir.join("core/src"))?;
+    fs::write(dir.join("core/src/lib.rs"), "")?;
+    fs::write(
+        dir.join("core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\nauthors.workspace = true\n",
+    )?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--workspace", "--license", "MIT", "--yes"])
+        .assert()
+        .success();
+
+    let license = fs::read_to_string(dir.join("core/LICENSE"))?;
+    assert!(license.lines().next().unwrap().ends_with(" Jane Doe"));
+
+    Ok(())
+}
+
+#[test]
+fn append_authors() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] =
+        Item::Value(Value::from_iter(vec!["John Doe <john@example.com>"]));
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "MIT", "--yes"])
+        .args(["-a", "Jane Doe"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with(" Jane Doe"));
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--license", "MIT", "--yes"])
+        .args(["-a", "Jane Doe", "-a", "John Doe", "--append-authors"])
+        // The same author may be listed under another name with the same email address.
+        .args(["-a", "J. Doe <JOHN@example.com>"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.join("LICENSE"))?
+        .lines()
+        .next()
+        .unwrap()
+        .ends_with(" John Doe, Jane Doe"));
+
+    Ok(())
+}
+
+#[test]
+fn no_manifest_update() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+    let manifest = fs::read_to_string(dir.join("Cargo.toml"))?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "-a",
+            "John Doe",
+            "--no-manifest-update",
+            "--yes",
+        ])
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(dir.join("LICENSE"))?.contains("John Doe"));
+    assert_eq!(fs::read_to_string(dir.join("Cargo.toml"))?, manifest);
+
+    Ok(())
+}
+
+#[test]
+fn check_header_licenses() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--lib", "--name", "foo"])
+        .assert()
+        .success();
+
+    let mut document = fs::read_to_string(dir.join("Cargo.toml"))?.parse::<Document>()?;
+    document["package"]["authors"] = Item::Value(Value::from_iter(vec!["John Doe"]));
+    document["package"]["license"] = toml_edit::value("MIT OR Apache-2.0");
+    fs::write(dir.join("Cargo.toml"), document.to_string())?;
+    fs::write(dir.join("src/lib.rs"), "// SPDX-License-Identifier: MIT\n")?;
+    fs::write(
+        dir.join("src/gpl.rs"),
+        "// SPDX-License-Identifier: GPL-3.0\n",
+    )?;
+
+    Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args(["apply-license", "--check"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains(
+        "src/gpl.rs declares SPDX-License-Identifier: GPL-3.0, which is not part of MIT OR \
+         Apache-2.0"
+    ));
+    assert!(!stderr.contains("src/lib.rs"));
+
+    Ok(())
+}
+
+#[test]
+fn json_report_with_manifest_update() -> Result<()> {
+    let dir = tempdir()?;
+    let dir = dir.path();
+
+    Command::new("cargo")
+        .current_dir(dir)
+        .args(["init", "--name", "foo"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("cargo-apply-license")?
+        .current_dir(dir)
+        .args([
+            "apply-license",
+            "--license",
+            "MIT",
+            "-a",
+            "John Doe",
+            "--yes",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["dry_run"], false);
+    assert_eq!(report["manifest_changes"].as_array().unwrap().len(), 1);
+    assert!(fs::read_to_string(dir.join("Cargo.toml"))?.contains("license = \"MIT\""));
+
     Ok(())
 }